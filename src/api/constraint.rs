@@ -1,52 +1,84 @@
-use crate::api::{ApiClient, Result, DEFAULT_NAMESPACE};
+use crate::api::{ApiClient, Result};
+use crate::error::Error;
+use crate::transport::{CallOptions, Route};
+use crate::wire::wire_enum;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Number;
+use std::time::Duration;
 
-pub struct ConstraintClient<'client> {
-    client: &'client ApiClient,
+#[derive(Debug, Clone)]
+pub struct ConstraintClient {
+    client: ApiClient,
+    options: CallOptions,
 }
 
-impl<'client> ConstraintClient<'client> {
-    pub fn new(client: &'client ApiClient) -> Self {
-        Self { client }
+impl ConstraintClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn in_namespace(mut self, namespace_key: &str) -> Self {
+        self.options.namespace_key = Some(namespace_key.into());
+        self
     }
 
     pub async fn create(&self, create: &ConstraintCreateRequest) -> Result<Constraint> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/segments/{segment_key}/constraints",
-            namespace_key = create
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            segment_key = create.segment_key
-        );
-        self.client.post(&path, Some(create)).await
+        let route =
+            Route::new("/api/v1/namespaces/{namespace_key}/segments/{segment_key}/constraints")
+                .namespace(
+                    self.client
+                        .transport
+                        .namespace_or(&create.namespace_key, &self.options),
+                )
+                .param("segment_key", &create.segment_key);
+        self.client
+            .transport
+            .post(route, Some(create), &self.options)
+            .await
     }
 
     pub async fn delete(&self, delete: &ConstraintDeleteRequest) -> Result<ConstraintDeletion> {
-        let path = format!(
+        let route = Route::new(
             "/api/v1/namespaces/{namespace_key}/segments/{segment_key}/constraints/{id}",
-            namespace_key = delete
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            segment_key = delete.segment_key,
-            id = delete.id
-        );
-        self.client.delete(&path, None::<&()>).await
+        )
+        .namespace(
+            self.client
+                .transport
+                .namespace_or(&delete.namespace_key, &self.options),
+        )
+        .param("segment_key", &delete.segment_key)
+        .param("id", &delete.id);
+        self.client
+            .transport
+            .delete(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn update(&self, update: &ConstraintUpdateRequest) -> Result<Constraint> {
-        let path = format!(
+        let route = Route::new(
             "/api/v1/namespaces/{namespace_key}/segments/{segment_key}/constraints/{id}",
-            namespace_key = update
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            segment_key = update.segment_key,
-            id = update.id
-        );
-        self.client.put(&path, Some(update)).await
+        )
+        .namespace(
+            self.client
+                .transport
+                .namespace_or(&update.namespace_key, &self.options),
+        )
+        .param("segment_key", &update.segment_key)
+        .param("id", &update.id);
+        self.client
+            .transport
+            .put(route, Some(update), &self.options)
+            .await
     }
 }
 
@@ -64,6 +96,33 @@ pub struct ConstraintCreateRequest {
     pub description: String,
 }
 
+impl ConstraintCreateRequest {
+    /// A constraint matching when `property` is one of `values`. Strings
+    /// are compared as [`ComparisonType::String`] and numbers as
+    /// [`ComparisonType::Number`]; set `comparison_type` to
+    /// [`ComparisonType::EntityId`] to match entity ids instead.
+    pub fn is_one_of(segment_key: &str, property: &str, values: impl Into<ValueSet>) -> Self {
+        Self::set(segment_key, property, Operator::IsOneOf, values.into())
+    }
+
+    /// A constraint matching when `property` is none of `values`. See
+    /// [`ConstraintCreateRequest::is_one_of`].
+    pub fn is_not_one_of(segment_key: &str, property: &str, values: impl Into<ValueSet>) -> Self {
+        Self::set(segment_key, property, Operator::IsNotOneOf, values.into())
+    }
+
+    fn set(segment_key: &str, property: &str, operator: Operator, values: ValueSet) -> Self {
+        Self {
+            segment_key: segment_key.into(),
+            operator,
+            property: property.into(),
+            comparison_type: values.comparison_type(),
+            value: values.to_value(),
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Debug, Default, Serialize)]
 pub struct ConstraintUpdateRequest {
     #[serde(skip_serializing)]
@@ -90,52 +149,113 @@ pub struct ConstraintDeleteRequest {
 #[derive(Debug, Clone, Deserialize)]
 pub struct ConstraintDeletion {}
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, Eq, PartialEq)]
-pub enum ComparisonType {
-    #[default]
-    #[serde(rename = "UNKNOWN_COMPARISON_TYPE")]
-    Unknown,
-    #[serde(rename = "STRING_COMPARISON_TYPE")]
-    String,
-    #[serde(rename = "NUMBER_COMPARISON_TYPE")]
-    Number,
-    #[serde(rename = "BOOLEAN_COMPARISON_TYPE")]
-    Boolean,
-    #[serde(rename = "DATETIME_COMPARISON_TYPE")]
-    DateTime,
-}
-
-#[derive(Debug, Default, Deserialize, Serialize, Clone, Eq, PartialEq)]
-pub enum Operator {
-    #[default]
-    #[serde(rename = "eq")]
-    Eq,
-    #[serde(rename = "neq")]
-    NotEq,
-    #[serde(rename = "lt")]
-    Lt,
-    #[serde(rename = "lte")]
-    Lte,
-    #[serde(rename = "gt")]
-    Gt,
-    #[serde(rename = "gte")]
-    Gte,
-    #[serde(rename = "empty")]
-    Empty,
-    #[serde(rename = "notempty")]
-    NotEmpty,
-    #[serde(rename = "true")]
-    True,
-    #[serde(rename = "false")]
-    False,
-    #[serde(rename = "present")]
-    Present,
-    #[serde(rename = "notpresent")]
-    NotPresent,
-    #[serde(rename = "prefix")]
-    Prefix,
-    #[serde(rename = "suffix")]
-    Suffix,
+wire_enum! {
+    #[derive(Default)]
+    pub enum ComparisonType {
+        #[default]
+        Unspecified = "UNKNOWN_COMPARISON_TYPE",
+        String = "STRING_COMPARISON_TYPE",
+        Number = "NUMBER_COMPARISON_TYPE",
+        Boolean = "BOOLEAN_COMPARISON_TYPE",
+        DateTime = "DATETIME_COMPARISON_TYPE",
+        /// Compares the entity id of the evaluation instead of a property
+        /// of its context.
+        EntityId = "ENTITY_ID_COMPARISON_TYPE",
+    }
+}
+
+wire_enum! {
+    #[derive(Default)]
+    pub enum Operator {
+        #[default]
+        Eq = "eq",
+        NotEq = "neq",
+        Lt = "lt",
+        Lte = "lte",
+        Gt = "gt",
+        Gte = "gte",
+        Empty = "empty",
+        NotEmpty = "notempty",
+        True = "true",
+        False = "false",
+        Present = "present",
+        NotPresent = "notpresent",
+        Prefix = "prefix",
+        Suffix = "suffix",
+        /// The value is one of a [`ValueSet`].
+        IsOneOf = "isoneof",
+        /// The value is none of a [`ValueSet`].
+        IsNotOneOf = "isnotoneof",
+    }
+}
+
+/// The values of an `isoneof` or `isnotoneof` constraint, sent as a JSON
+/// array in its `value`.
+///
+/// Integers convert directly; floating point numbers go through `try_from`,
+/// since NaN and infinity cannot be written as JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueSet {
+    Strings(Vec<String>),
+    Numbers(Vec<Number>),
+}
+
+impl ValueSet {
+    /// The JSON array sent as the constraint `value`.
+    pub fn to_value(&self) -> String {
+        match self {
+            ValueSet::Strings(v) => serde_json::Value::from(v.clone()),
+            ValueSet::Numbers(v) => serde_json::Value::from(v.clone()),
+        }
+        .to_string()
+    }
+
+    fn comparison_type(&self) -> ComparisonType {
+        match self {
+            ValueSet::Strings(_) => ComparisonType::String,
+            ValueSet::Numbers(_) => ComparisonType::Number,
+        }
+    }
+}
+
+impl From<Vec<String>> for ValueSet {
+    fn from(v: Vec<String>) -> Self {
+        ValueSet::Strings(v)
+    }
+}
+
+impl From<Vec<&str>> for ValueSet {
+    fn from(v: Vec<&str>) -> Self {
+        ValueSet::Strings(v.into_iter().map(String::from).collect())
+    }
+}
+
+impl From<Vec<Number>> for ValueSet {
+    fn from(v: Vec<Number>) -> Self {
+        ValueSet::Numbers(v)
+    }
+}
+
+impl From<Vec<i64>> for ValueSet {
+    fn from(v: Vec<i64>) -> Self {
+        ValueSet::Numbers(v.into_iter().map(Number::from).collect())
+    }
+}
+
+impl TryFrom<Vec<f64>> for ValueSet {
+    type Error = Error;
+
+    /// Fails with [`Error::InvalidArgument`] for NaN or infinite values.
+    fn try_from(v: Vec<f64>) -> Result<Self> {
+        v.into_iter()
+            .map(|n| {
+                Number::from_f64(n).ok_or_else(|| {
+                    Error::invalid_argument(format!("{n} cannot be used in a value set"))
+                })
+            })
+            .collect::<Result<_>>()
+            .map(ValueSet::Numbers)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
@@ -151,3 +271,22 @@ pub struct Constraint {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+impl Constraint {
+    /// The values of an `isoneof` or `isnotoneof` constraint, parsed from
+    /// `value`. Absent for other operators or when `value` is not a JSON
+    /// array of the comparison type.
+    pub fn value_set(&self) -> Option<ValueSet> {
+        if !matches!(self.operator, Operator::IsOneOf | Operator::IsNotOneOf) {
+            return None;
+        }
+        match self.comparison_type {
+            ComparisonType::Number => serde_json::from_str(&self.value)
+                .ok()
+                .map(ValueSet::Numbers),
+            _ => serde_json::from_str(&self.value)
+                .ok()
+                .map(ValueSet::Strings),
+        }
+    }
+}
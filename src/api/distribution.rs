@@ -1,48 +1,65 @@
-use crate::api::{ApiClient, Result, DEFAULT_NAMESPACE};
+use crate::api::{ApiClient, Result};
+use crate::transport::{CallOptions, Route};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-pub struct DistributionClient<'client> {
-    client: &'client ApiClient,
+#[derive(Debug, Clone)]
+pub struct DistributionClient {
+    client: ApiClient,
+    options: CallOptions,
 }
 
-impl<'client> DistributionClient<'client> {
-    pub fn new(client: &'client ApiClient) -> Self {
-        Self { client }
+impl DistributionClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn in_namespace(mut self, namespace_key: &str) -> Self {
+        self.options.namespace_key = Some(namespace_key.into());
+        self
     }
 
     pub async fn create(&self, create: &DistributionCreateRequest) -> Result<Distribution> {
-        let path = format!(
+        let route = Route::new(
             "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{rule_id}/distributions",
-            namespace_key = create
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = create.flag_key,
-            rule_id = create.rule_id
-        );
-        self.client.post(&path, Some(create)).await
+        )
+        .namespace(
+            self.client
+                .transport
+                .namespace_or(&create.namespace_key, &self.options),
+        )
+        .flag(&create.flag_key)
+        .param("rule_id", &create.rule_id);
+        self.client
+            .transport
+            .post(route, Some(create), &self.options)
+            .await
     }
 
     pub async fn delete(&self, delete: &DistributionDeleteRequest) -> Result<DistributionDeletion> {
-        let path =
-            format!(
-            "/api/v1/namespace/{namespace_key}/flags/{flag_key}/rules/{rule_id}/distributions/{id}",
-            namespace_key = delete.namespace_key.as_ref().unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = delete.flag_key,
-            rule_id = delete.rule_id,
-            id = delete.id
-        );
-        self.client.delete(&path, None::<&()>).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{rule_id}/distributions/{id}").namespace(self.client.transport.namespace_or(&delete.namespace_key, &self.options)).flag(&delete.flag_key).param("rule_id", &delete.rule_id).param("id", &delete.id);
+        self.client
+            .transport
+            .delete(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn update(&self, update: &DistributionUpdateRequest) -> Result<Distribution> {
-        let path = format!("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{rule_id}/distributions/{id}",
-            namespace_key = update.namespace_key.as_ref().unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = update.flag_key,
-            rule_id = update.rule_id,
-            id = update.id);
-        self.client.put(&path, Some(update)).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{rule_id}/distributions/{id}").namespace(self.client.transport.namespace_or(&update.namespace_key, &self.options)).flag(&update.flag_key).param("rule_id", &update.rule_id).param("id", &update.id);
+        self.client
+            .transport
+            .put(route, Some(update), &self.options)
+            .await
     }
 }
 
@@ -1,37 +1,53 @@
-use crate::api::{ApiClient, Result, DEFAULT_NAMESPACE};
+use crate::api::{ApiClient, Result};
+use crate::transport::{CallOptions, Route};
+use crate::wire::wire_enum;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
-pub struct EvaluationClient<'client> {
-    client: &'client ApiClient,
+#[derive(Debug, Clone)]
+pub struct EvaluationClient {
+    client: ApiClient,
+    options: CallOptions,
 }
 
-impl<'client> EvaluationClient<'client> {
-    pub fn new(client: &'client ApiClient) -> Self {
-        Self { client }
+impl EvaluationClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
     }
 
     pub async fn evaluate(&self, eval: &EvaluateRequest) -> Result<Evaluation> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/evaluate",
-            namespace_key = eval
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string())
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/evaluate").namespace(
+            self.client
+                .transport
+                .namespace_or(&eval.namespace_key, &self.options),
         );
-        self.client.post(&path, Some(eval)).await
+        self.client
+            .transport
+            .post_idempotent(route, Some(eval), &self.options)
+            .await
     }
 
     pub async fn evaluate_batch(&self, batch: &BatchEvaluateRequest) -> Result<Evaluation> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/batch-evaluate",
-            namespace_key = batch
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string())
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/batch-evaluate").namespace(
+            self.client
+                .transport
+                .namespace_or(&batch.namespace_key, &self.options),
         );
-        self.client.post(&path, Some(batch)).await
+        self.client
+            .transport
+            .post_idempotent(route, Some(batch), &self.options)
+            .await
     }
 }
 
@@ -82,18 +98,21 @@ pub struct Evaluation {
     pub value: String,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
-pub enum Reason {
-    #[serde(rename = "UNKNOWN_EVALUATION_REASON")]
-    Unknown,
-    #[serde(rename = "FLAG_DISABLED_EVALUATION_REASON")]
-    FlagDisabled,
-    #[serde(rename = "FLAG_NOT_FOUND_EVALUATION_REASON")]
-    FlagNotFound,
-    #[serde(rename = "MATCH_EVALUATION_REASON")]
-    Match,
-    #[serde(rename = "ERROR_EVALUATION_REASON")]
-    Error,
-    #[serde(rename = "DEFAULT_EVALUATION_REASON")]
-    Default,
+impl Evaluation {
+    /// Whether no rule matched and the default variant of the flag was
+    /// served.
+    pub fn is_default_variant(&self) -> bool {
+        self.reason == Reason::Default && !self.value.is_empty()
+    }
+}
+
+wire_enum! {
+    pub enum Reason {
+        Unspecified = "UNKNOWN_EVALUATION_REASON",
+        FlagDisabled = "FLAG_DISABLED_EVALUATION_REASON",
+        FlagNotFound = "FLAG_NOT_FOUND_EVALUATION_REASON",
+        Match = "MATCH_EVALUATION_REASON",
+        Error = "ERROR_EVALUATION_REASON",
+        Default = "DEFAULT_EVALUATION_REASON",
+    }
 }
@@ -1,73 +1,136 @@
 use crate::api::variant::Variant;
-use crate::api::{ApiClient, Result, DEFAULT_LIMIT, DEFAULT_NAMESPACE};
+use crate::api::{ApiClient, Result, DEFAULT_LIMIT};
+use crate::pagination::{Page, PageStream};
+use crate::transport::{CallOptions, Route};
+use crate::wire::wire_enum;
 use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::time::Duration;
 
-pub struct FlagClient<'client> {
-    client: &'client ApiClient,
+#[derive(Debug, Clone)]
+pub struct FlagClient {
+    client: ApiClient,
+    options: CallOptions,
 }
 
-impl<'client> FlagClient<'client> {
-    pub fn new(client: &'client ApiClient) -> Self {
-        Self { client }
+impl FlagClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn in_namespace(mut self, namespace_key: &str) -> Self {
+        self.options.namespace_key = Some(namespace_key.into());
+        self
     }
 
     pub async fn list(&self, list: &FlagListRequest) -> Result<FlagList> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags",
-            namespace_key = list
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string())
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags").namespace(
+            self.client
+                .transport
+                .namespace_or(&list.namespace_key, &self.options),
         );
-        self.client.get(&path, Some(list)).await
+        self.client
+            .transport
+            .get(route, Some(list), &self.options)
+            .await
+    }
+
+    /// Streams the flags matching `list` across all pages, starting
+    /// from its `page_token` and requesting `limit` items per page.
+    pub fn stream(&self, list: &FlagListRequest) -> PageStream<Flag> {
+        let client = self.clone();
+        let namespace_key = list.namespace_key.clone();
+        let offset = list.offset;
+        PageStream::new(
+            list.page_token.clone(),
+            list.limit,
+            move |page_token, limit| {
+                let client = client.clone();
+                let list = FlagListRequest {
+                    namespace_key: namespace_key.clone(),
+                    offset,
+                    limit,
+                    page_token,
+                };
+                Box::pin(async move {
+                    let res = client.list(&list).await?;
+                    Ok(Page {
+                        items: res.flags,
+                        next_page_token: res.next_page_token,
+                    })
+                })
+            },
+        )
+    }
+
+    /// Fetches all the flags matching `list` across all pages.
+    pub async fn list_all(&self, list: &FlagListRequest) -> Result<Vec<Flag>> {
+        self.stream(list).try_collect().await
     }
 
     pub async fn get(&self, get: &FlagGetRequest) -> Result<Flag> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{key}",
-            namespace_key = get
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            key = get.key
-        );
-        self.client.get(&path, None::<&()>).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&get.namespace_key, &self.options),
+            )
+            .flag(&get.key);
+        self.client
+            .transport
+            .get(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn create(&self, create: &FlagCreateRequest) -> Result<Flag> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags",
-            namespace_key = create
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags").namespace(
+            self.client
+                .transport
+                .namespace_or(&create.namespace_key, &self.options),
         );
-        self.client.post(&path, Some(create)).await
+        self.client
+            .transport
+            .post(route, Some(create), &self.options)
+            .await
     }
 
     pub async fn delete(&self, delete: &FlagDeleteRequest) -> Result<FlagDeletion> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{key}",
-            namespace_key = delete
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            key = delete.key
-        );
-        self.client.delete(&path, None::<&()>).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&delete.namespace_key, &self.options),
+            )
+            .flag(&delete.key);
+        self.client
+            .transport
+            .delete(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn update(&self, update: &FlagUpdateRequest) -> Result<Flag> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{key}",
-            namespace_key = update
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            key = update.key
-        );
-        self.client.put(&path, Some(update)).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&update.namespace_key, &self.options),
+            )
+            .flag(&update.key);
+        self.client
+            .transport
+            .put(route, Some(update), &self.options)
+            .await
     }
 }
 
@@ -90,6 +153,8 @@ pub struct FlagCreateRequest {
     pub description: String,
     pub enabled: bool,
     pub r#type: Option<FlagType>,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub metadata: Map<String, Value>,
 }
 
 #[derive(Debug, Serialize, Default)]
@@ -102,6 +167,14 @@ pub struct FlagUpdateRequest {
     pub name: String,
     pub description: String,
     pub enabled: bool,
+    /// Replaces the metadata of the flag. Unset leaves it unchanged and an
+    /// empty map clears it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Map<String, Value>>,
+    /// The id of the variant served when no rule matches. Unset removes the
+    /// default variant.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_variant_id: Option<String>,
 }
 
 #[derive(Debug, Default)]
@@ -154,6 +227,10 @@ pub struct Flag {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub variants: Vec<Variant>,
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+    /// The variant served when no rule matches.
+    pub default_variant: Option<Variant>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -164,11 +241,11 @@ pub struct FlagList {
     pub total_count: usize,
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
-pub enum FlagType {
-    #[default]
-    #[serde(rename = "VARIANT_FLAG_TYPE")]
-    Variant,
-    #[serde(rename = "BOOLEAN_FLAG_TYPE")]
-    Boolean,
+wire_enum! {
+    #[derive(Default)]
+    pub enum FlagType {
+        #[default]
+        Variant = "VARIANT_FLAG_TYPE",
+        Boolean = "BOOLEAN_FLAG_TYPE",
+    }
 }
@@ -3,38 +3,37 @@ pub mod distribution;
 pub mod evaluation;
 pub mod flag;
 pub mod namespace;
+pub mod rank;
 pub mod rollout;
 pub mod rule;
 pub mod segment;
 pub mod variant;
 
-use crate::error::{Error, UpstreamError};
+use crate::transport::Transport;
 use crate::{AuthScheme, Config};
-use url::Url;
 
 const DEFAULT_LIMIT: usize = 100;
-const DEFAULT_NAMESPACE: &str = "default";
 
-pub type Result<T> = anyhow::Result<T>;
+pub use crate::transport::{deserialize, Result};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ApiClient {
-    client: reqwest::Client,
-    auth_scheme: AuthScheme,
-    endpoint: Url,
+    pub(crate) transport: Transport,
 }
 
 impl ApiClient {
     pub fn new(config: Config) -> Result<Self> {
-        let client = reqwest::Client::builder()
-            .user_agent(config.user_agent)
-            .build()?;
+        Ok(Self::from_transport(Transport::new(config)?))
+    }
+
+    pub(crate) fn from_transport(transport: Transport) -> Self {
+        Self { transport }
+    }
 
-        Ok(Self {
-            client,
-            auth_scheme: config.auth_scheme,
-            endpoint: config.endpoint,
-        })
+    /// Replaces the credentials sent by this client and every client sharing
+    /// its connection pool.
+    pub fn set_auth_scheme(&self, v: AuthScheme) {
+        self.transport.set_auth_scheme(v);
     }
 
     pub fn flags(&self) -> flag::FlagClient {
@@ -73,85 +72,58 @@ impl ApiClient {
         namespace::NamespaceClient::new(self)
     }
 
-    pub(crate) async fn get<P, R>(&self, path: &str, params: Option<&P>) -> Result<R>
-    where
-        P: serde::Serialize,
-        R: serde::de::DeserializeOwned,
-    {
-        let url = self.build_url(path)?;
-        let mut request = self.client.get(url);
-        if let Some(params) = params {
-            request = request.query(params);
-        }
-        let resp = self.send(request).await?;
-        deserialize(resp).await
-    }
-
-    pub(crate) async fn post<B, R>(&self, path: &str, body: Option<&B>) -> Result<R>
-    where
-        B: serde::Serialize,
-        R: serde::de::DeserializeOwned,
-    {
-        let url = self.build_url(path)?;
-        let mut request = self.client.post(url);
-        if let Some(body) = body {
-            request = request
-                .header("Content-Type", "application/json")
-                .json(body);
+    /// Sub-clients whose requests default to `namespace_key` instead of the
+    /// configured namespace.
+    pub fn namespace(&self, namespace_key: &str) -> NamespacedClient {
+        NamespacedClient {
+            client: self.clone(),
+            namespace_key: namespace_key.into(),
         }
-        let resp = self.send(request).await?;
-        deserialize(resp).await
-    }
-
-    pub(crate) async fn put<B, R>(&self, path: &str, body: Option<&B>) -> Result<R>
-    where
-        B: serde::Serialize,
-        R: serde::de::DeserializeOwned,
-    {
-        let url = self.build_url(path)?;
-        let mut request = self.client.put(url);
-        if let Some(body) = body {
-            request = request
-                .header("Content-Type", "application/json")
-                .json(body);
-        }
-        let resp = self.send(request).await?;
-        deserialize(resp).await
-    }
-
-    pub(crate) async fn delete<P, R>(&self, path: &str, params: Option<&P>) -> Result<R>
-    where
-        P: serde::Serialize,
-        R: serde::de::DeserializeOwned,
-    {
-        let url = self.build_url(path)?;
-        let mut request = self.client.delete(url);
-        if let Some(params) = params {
-            request = request.query(params);
-        }
-        let resp = self.send(request).await?;
-        deserialize(resp).await
     }
+}
 
-    async fn send(&self, mut request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
-        match self.auth_scheme {
-            AuthScheme::None => {}
-            AuthScheme::BearerToken(ref token) => {
-                request = request.bearer_auth(token);
-            }
-        }
-        Ok(request.send().await?)
+/// Sub-clients scoped to a namespace. A `namespace_key` set on a request
+/// still takes precedence.
+#[derive(Debug, Clone)]
+pub struct NamespacedClient {
+    client: ApiClient,
+    namespace_key: String,
+}
+
+impl NamespacedClient {
+    pub fn flags(&self) -> flag::FlagClient {
+        self.client.flags().in_namespace(&self.namespace_key)
     }
 
-    fn build_url(&self, path: &str) -> Result<Url> {
-        Ok(self.endpoint.join(path)?)
+    pub fn variants(&self) -> variant::VariantClient {
+        self.client.variants().in_namespace(&self.namespace_key)
+    }
+
+    pub fn segments(&self) -> segment::SegmentClient {
+        self.client.segments().in_namespace(&self.namespace_key)
+    }
+
+    pub fn constraints(&self) -> constraint::ConstraintClient {
+        self.client.constraints().in_namespace(&self.namespace_key)
+    }
+
+    pub fn distributions(&self) -> distribution::DistributionClient {
+        self.client
+            .distributions()
+            .in_namespace(&self.namespace_key)
+    }
+
+    pub fn rollouts(&self) -> rollout::RolloutClient {
+        self.client.rollouts().in_namespace(&self.namespace_key)
+    }
+
+    pub fn rules(&self) -> rule::RuleClient {
+        self.client.rules().in_namespace(&self.namespace_key)
     }
-}
 
-pub async fn deserialize<T: serde::de::DeserializeOwned>(resp: reqwest::Response) -> Result<T> {
-    if resp.status().is_success() {
-        return Ok(resp.json::<T>().await?);
+    /// Flag evaluation with requests that leave `namespace_key` empty
+    /// evaluated in this namespace.
+    pub fn evaluation(&self) -> crate::evaluation::EvaluationClient {
+        crate::evaluation::EvaluationClient::new(&self.client).in_namespace(&self.namespace_key)
     }
-    let parsed_err = resp.json::<UpstreamError>().await?;
-    Err(anyhow::Error::new(Error::Upstream(parsed_err)))
 }
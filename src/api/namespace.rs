@@ -1,37 +1,102 @@
 use crate::api::{ApiClient, Result, DEFAULT_LIMIT};
+use crate::pagination::{Page, PageStream};
+use crate::transport::{CallOptions, Route};
 use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-pub struct NamespaceClient<'client> {
-    client: &'client ApiClient,
+#[derive(Debug, Clone)]
+pub struct NamespaceClient {
+    client: ApiClient,
+    options: CallOptions,
 }
 
-impl<'client> NamespaceClient<'client> {
-    pub fn new(client: &'client ApiClient) -> Self {
-        Self { client }
+impl NamespaceClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
     }
 
     pub async fn list(&self, list: &NamespaceListRequest) -> Result<NamespaceList> {
-        self.client.get("/api/v1/namespaces", Some(list)).await
+        self.client
+            .transport
+            .get(Route::new("/api/v1/namespaces"), Some(list), &self.options)
+            .await
+    }
+
+    /// Streams the namespaces matching `list` across all pages, starting
+    /// from its `page_token` and requesting `limit` items per page.
+    pub fn stream(&self, list: &NamespaceListRequest) -> PageStream<Namespace> {
+        let client = self.clone();
+        let offset = list.offset;
+        PageStream::new(
+            list.page_token.clone(),
+            list.limit,
+            move |page_token, limit| {
+                let client = client.clone();
+                let list = NamespaceListRequest {
+                    offset,
+                    limit,
+                    page_token,
+                };
+                Box::pin(async move {
+                    let res = client.list(&list).await?;
+                    Ok(Page {
+                        items: res.namespaces,
+                        next_page_token: res.next_page_token,
+                    })
+                })
+            },
+        )
+    }
+
+    /// Fetches all the namespaces matching `list` across all pages.
+    pub async fn list_all(&self, list: &NamespaceListRequest) -> Result<Vec<Namespace>> {
+        self.stream(list).try_collect().await
     }
 
     pub async fn get(&self, get: &NamespaceGetRequest) -> Result<Namespace> {
-        let path = format!("/api/v1/namespaces/{key}", key = get.key);
-        self.client.get(&path, None::<&()>).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}").namespace(&get.key);
+        self.client
+            .transport
+            .get(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn create(&self, create: &NamespaceCreateRequest) -> Result<Namespace> {
-        self.client.post("/api/v1/namespaces", Some(create)).await
+        self.client
+            .transport
+            .post(
+                Route::new("/api/v1/namespaces"),
+                Some(create),
+                &self.options,
+            )
+            .await
     }
 
     pub async fn delete(&self, delete: &NamespaceDeleteRequest) -> Result<NamespaceDeletion> {
-        let path = format!("/api/v1/namespaces/{key}", key = delete.key);
-        self.client.delete(&path, None::<&()>).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}").namespace(&delete.key);
+        self.client
+            .transport
+            .delete(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn update(&self, update: &NamespaceUpdateRequest) -> Result<Namespace> {
-        let path = format!("/api/v1/namespaces/{key}", key = update.key);
-        self.client.put(&path, Some(update)).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}").namespace(&update.key);
+        self.client
+            .transport
+            .put(route, Some(update), &self.options)
+            .await
     }
 }
 
@@ -0,0 +1,114 @@
+//! Reordering of the ranked resources of a flag: rules and rollouts.
+//!
+//! Every operation fetches the current order, computes the new one and
+//! submits it in a single call. Flipt has no conditional updates, so
+//! concurrent changes are detected on a best-effort basis only: the order is
+//! read again right before submitting and nothing is written when it changed,
+//! and it is read back afterwards. Either mismatch is an
+//! [`Error::Conflict`]. A change landing between the second read and the
+//! write can still be overwritten.
+
+use crate::api::rollout::Rollout;
+use crate::api::rule::Rule;
+use crate::error::{Error, Result};
+use std::future::Future;
+
+/// Where to move a rule or rollout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// First, evaluated before all others.
+    Top,
+    /// Last, evaluated after all others.
+    Bottom,
+    /// One place earlier. Stays in place when already first.
+    Up,
+    /// One place later. Stays in place when already last.
+    Down,
+    /// The given rank, starting at 1 as in the `rank` fields. Ranks past the
+    /// end move to the bottom.
+    Rank(u32),
+}
+
+pub(crate) trait Ranked {
+    fn id(&self) -> &str;
+}
+
+impl Ranked for Rule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Ranked for Rollout {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// Moves `id` to `position` in `ids`.
+pub(crate) fn move_to(ids: &mut Vec<String>, id: &str, position: Position) -> Result<()> {
+    let from = index_of(ids, id)?;
+    let last = ids.len() - 1;
+    let to = match position {
+        Position::Top => 0,
+        Position::Bottom => last,
+        Position::Up => from.saturating_sub(1),
+        Position::Down => (from + 1).min(last),
+        Position::Rank(rank) => (rank.max(1) as usize - 1).min(last),
+    };
+    let id = ids.remove(from);
+    ids.insert(to, id);
+    Ok(())
+}
+
+pub(crate) fn swap(ids: &mut [String], first: &str, second: &str) -> Result<()> {
+    let first = index_of(ids, first)?;
+    let second = index_of(ids, second)?;
+    ids.swap(first, second);
+    Ok(())
+}
+
+fn index_of(ids: &[String], id: &str) -> Result<usize> {
+    ids.iter()
+        .position(|v| v == id)
+        .ok_or_else(|| Error::not_found(format!("{id} is not in the current order")))
+}
+
+/// Applies `change` to the current order of the items returned by `list`,
+/// submits the result with `order` unless the order changed in the meantime,
+/// and checks that it took effect. Returns the items in their new order.
+pub(crate) async fn reorder<T, L, LF, O, OF, C>(list: L, order: O, change: C) -> Result<Vec<T>>
+where
+    T: Ranked,
+    L: Fn() -> LF,
+    LF: Future<Output = Result<Vec<T>>>,
+    O: FnOnce(Vec<String>) -> OF,
+    OF: Future<Output = Result<()>>,
+    C: FnOnce(&mut Vec<String>) -> Result<()>,
+{
+    let items = list().await?;
+    let current = ids(&items);
+    let mut wanted = current.clone();
+    change(&mut wanted)?;
+    if wanted == current {
+        return Ok(items);
+    }
+
+    if ids(&list().await?) != current {
+        return Err(Error::Conflict(
+            "the order changed before it could be updated".into(),
+        ));
+    }
+    order(wanted.clone()).await?;
+    let items = list().await?;
+    if ids(&items) != wanted {
+        return Err(Error::Conflict(
+            "the order changed while it was being updated".into(),
+        ));
+    }
+    Ok(items)
+}
+
+fn ids<T: Ranked>(items: &[T]) -> Vec<String> {
+    items.iter().map(|v| v.id().to_string()).collect()
+}
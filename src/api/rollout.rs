@@ -1,82 +1,257 @@
-use crate::api::{ApiClient, Result, DEFAULT_NAMESPACE};
+use crate::api::rank::{self, Position};
+use crate::api::{ApiClient, Result, DEFAULT_LIMIT};
+use crate::pagination::{Page, PageStream};
+use crate::transport::{CallOptions, Route};
+use crate::wire::wire_enum;
 use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-pub struct RolloutClient<'client> {
-    client: &'client ApiClient,
+#[derive(Debug, Clone)]
+pub struct RolloutClient {
+    client: ApiClient,
+    options: CallOptions,
 }
 
-impl<'client> RolloutClient<'client> {
-    pub fn new(client: &'client ApiClient) -> Self {
-        Self { client }
+impl RolloutClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn in_namespace(mut self, namespace_key: &str) -> Self {
+        self.options.namespace_key = Some(namespace_key.into());
+        self
+    }
+
+    /// Lists the rollouts of a flag in rank order.
+    pub async fn list(&self, list: &RolloutListRequest) -> Result<RolloutList> {
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&list.namespace_key, &self.options),
+            )
+            .flag(&list.flag_key);
+
+        self.client
+            .transport
+            .get(route, Some(list), &self.options)
+            .await
+    }
+
+    /// Streams the rollouts matching `list` across all pages, starting
+    /// from its `page_token` and requesting `limit` items per page.
+    pub fn stream(&self, list: &RolloutListRequest) -> PageStream<Rollout> {
+        let client = self.clone();
+        let namespace_key = list.namespace_key.clone();
+        let flag_key = list.flag_key.clone();
+        PageStream::new(
+            list.page_token.clone(),
+            list.limit,
+            move |page_token, limit| {
+                let client = client.clone();
+                let list = RolloutListRequest {
+                    namespace_key: namespace_key.clone(),
+                    flag_key: flag_key.clone(),
+                    limit,
+                    page_token,
+                };
+                Box::pin(async move {
+                    let res = client.list(&list).await?;
+                    Ok(Page {
+                        items: res.rollouts,
+                        next_page_token: res.next_page_token,
+                    })
+                })
+            },
+        )
+    }
+
+    /// Fetches all the rollouts matching `list` across all pages.
+    pub async fn list_all(&self, list: &RolloutListRequest) -> Result<Vec<Rollout>> {
+        self.stream(list).try_collect().await
     }
 
     pub async fn get(&self, get: &RolloutGetRequest) -> Result<Rollout> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts/{id}",
-            namespace_key = get
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = get.flag_key,
-            id = get.id
-        );
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts/{id}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&get.namespace_key, &self.options),
+            )
+            .flag(&get.flag_key)
+            .param("id", &get.id);
 
-        self.client.get(&path, None::<&()>).await
+        self.client
+            .transport
+            .get(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn create(&self, create: &RolloutCreateRequest) -> Result<Rollout> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts",
-            namespace_key = create
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = create.flag_key
-        );
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&create.namespace_key, &self.options),
+            )
+            .flag(&create.flag_key);
 
-        self.client.post(&path, Some(create)).await
+        self.client
+            .transport
+            .post(route, Some(create), &self.options)
+            .await
     }
 
     pub async fn delete(&self, delete: &RolloutDeleteRequest) -> Result<Empty> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts/{id}",
-            namespace_key = delete
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = delete.flag_key,
-            id = delete.id
-        );
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts/{id}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&delete.namespace_key, &self.options),
+            )
+            .flag(&delete.flag_key)
+            .param("id", &delete.id);
 
-        self.client.delete(&path, None::<&()>).await
+        self.client
+            .transport
+            .delete(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn update(&self, update: &RolloutUpdateRequest) -> Result<Rollout> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts/{id}",
-            namespace_key = update
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = update.flag_key,
-            id = update.id
-        );
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts/{id}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&update.namespace_key, &self.options),
+            )
+            .flag(&update.flag_key)
+            .param("id", &update.id);
 
-        self.client.put(&path, Some(update)).await
+        self.client
+            .transport
+            .put(route, Some(update), &self.options)
+            .await
     }
 
     pub async fn order(&self, order: &RolloutOrderRequest) -> Result<Empty> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts/order",
-            namespace_key = order
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = order.flag_key,
-        );
+        let route =
+            Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rollouts/order")
+                .namespace(
+                    self.client
+                        .transport
+                        .namespace_or(&order.namespace_key, &self.options),
+                )
+                .flag(&order.flag_key);
+
+        self.client
+            .transport
+            .put(route, Some(order), &self.options)
+            .await
+    }
 
-        self.client.put(&path, Some(order)).await
+    /// Moves a rollout and returns the rollouts of the flag in their new
+    /// order.
+    pub async fn move_to(&self, move_to: &RolloutMoveRequest) -> Result<Vec<Rollout>> {
+        self.reorder(&move_to.namespace_key, &move_to.flag_key, |ids| {
+            rank::move_to(ids, &move_to.id, move_to.position)
+        })
+        .await
+    }
+
+    /// Swaps two rollouts and returns the rollouts of the flag in their new
+    /// order.
+    pub async fn swap(&self, swap: &RolloutSwapRequest) -> Result<Vec<Rollout>> {
+        self.reorder(&swap.namespace_key, &swap.flag_key, |ids| {
+            rank::swap(ids, &swap.first, &swap.second)
+        })
+        .await
+    }
+
+    /// Creates a rollout at `position` among the rollouts of its flag. The
+    /// `rank` of `create` is ignored. When the rollout cannot be moved into
+    /// place, it is deleted again before the error is returned.
+    pub async fn create_at(
+        &self,
+        create: &RolloutCreateRequest,
+        position: Position,
+    ) -> Result<Rollout> {
+        let count = self
+            .list_all(&RolloutListRequest {
+                namespace_key: create.namespace_key.clone(),
+                flag_key: create.flag_key.clone(),
+                ..Default::default()
+            })
+            .await?
+            .len();
+        let rollout = self
+            .create(&RolloutCreateRequest {
+                rank: count + 1,
+                ..create.clone()
+            })
+            .await?;
+
+        let rollouts = match self
+            .reorder(&create.namespace_key, &create.flag_key, |ids| {
+                rank::move_to(ids, &rollout.id, position)
+            })
+            .await
+        {
+            Ok(rollouts) => rollouts,
+            Err(err) => {
+                // Leave nothing behind for a caller that retries.
+                let _ = self
+                    .delete(&RolloutDeleteRequest {
+                        namespace_key: create.namespace_key.clone(),
+                        flag_key: create.flag_key.clone(),
+                        id: rollout.id,
+                    })
+                    .await;
+                return Err(err);
+            }
+        };
+        Ok(rollouts
+            .into_iter()
+            .find(|r| r.id == rollout.id)
+            .unwrap_or(rollout))
+    }
+
+    async fn reorder(
+        &self,
+        namespace_key: &Option<String>,
+        flag_key: &str,
+        change: impl FnOnce(&mut Vec<String>) -> Result<()>,
+    ) -> Result<Vec<Rollout>> {
+        let list = RolloutListRequest {
+            namespace_key: namespace_key.clone(),
+            flag_key: flag_key.into(),
+            ..Default::default()
+        };
+        rank::reorder(
+            || self.list_all(&list),
+            |rollout_ids| async move {
+                self.order(&RolloutOrderRequest {
+                    namespace_key: namespace_key.clone(),
+                    flag_key: flag_key.into(),
+                    rollout_ids,
+                })
+                .await
+                .map(|_| ())
+            },
+            change,
+        )
+        .await
     }
 }
 
@@ -93,6 +268,50 @@ pub struct RolloutOrderRequest {
     pub rollout_ids: Vec<String>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloutListRequest {
+    #[serde(skip_serializing)]
+    pub namespace_key: Option<String>,
+    #[serde(skip_serializing)]
+    pub flag_key: String,
+    pub limit: usize,
+    pub page_token: String,
+}
+
+impl Default for RolloutListRequest {
+    fn default() -> Self {
+        Self {
+            namespace_key: None,
+            flag_key: "".to_owned(),
+            limit: DEFAULT_LIMIT,
+            page_token: "".to_owned(),
+        }
+    }
+}
+
+impl RolloutListRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug)]
+pub struct RolloutMoveRequest {
+    pub namespace_key: Option<String>,
+    pub flag_key: String,
+    pub id: String,
+    pub position: Position,
+}
+
+#[derive(Debug, Default)]
+pub struct RolloutSwapRequest {
+    pub namespace_key: Option<String>,
+    pub flag_key: String,
+    pub first: String,
+    pub second: String,
+}
+
 #[derive(Debug, Default, Serialize)]
 pub struct RolloutGetRequest {
     pub id: String,
@@ -100,7 +319,7 @@ pub struct RolloutGetRequest {
     pub flag_key: String,
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct RolloutCreateRequest {
     #[serde(skip_serializing)]
     pub namespace_key: Option<String>,
@@ -131,14 +350,12 @@ pub struct RolloutDeleteRequest {
     pub id: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
-pub enum RolloutType {
-    #[serde(rename = "UNKNOWN_ROLLOUT_TYPE")]
-    Unknown,
-    #[serde(rename = "SEGMENT_ROLLOUT_TYPE")]
-    Segment,
-    #[serde(rename = "THRESHOLD_ROLLOUT_TYPE")]
-    Threshold,
+wire_enum! {
+    pub enum RolloutType {
+        Unspecified = "UNKNOWN_ROLLOUT_TYPE",
+        Segment = "SEGMENT_ROLLOUT_TYPE",
+        Threshold = "THRESHOLD_ROLLOUT_TYPE",
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -170,11 +387,21 @@ pub struct RolloutSegment {
     pub value: bool,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
-pub enum SegmentOperator {
-    #[default]
-    #[serde(rename = "OR_SEGMENT_OPERATOR")]
-    Or,
-    #[serde(rename = "AND_SEGMENT_OPERATOR")]
-    And,
+wire_enum! {
+    #[derive(Default)]
+    pub enum SegmentOperator {
+        #[default]
+        Or = "OR_SEGMENT_OPERATOR",
+        And = "AND_SEGMENT_OPERATOR",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloutList {
+    /// Flipt sends the rollouts of a flag under `rules`.
+    #[serde(rename = "rules", alias = "rollouts")]
+    pub rollouts: Vec<Rollout>,
+    pub next_page_token: String,
+    pub total_count: u32,
 }
@@ -1,78 +1,244 @@
 use crate::api::distribution::Distribution;
-use crate::api::{ApiClient, Result, DEFAULT_LIMIT, DEFAULT_NAMESPACE};
+use crate::api::rank::{self, Position};
+use crate::api::{ApiClient, Result, DEFAULT_LIMIT};
+use crate::pagination::{Page, PageStream};
+use crate::transport::{CallOptions, Route};
+use crate::wire::wire_enum;
 use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-pub struct RuleClient<'client> {
-    client: &'client ApiClient,
+#[derive(Debug, Clone)]
+pub struct RuleClient {
+    client: ApiClient,
+    options: CallOptions,
 }
 
-impl<'client> RuleClient<'client> {
-    pub fn new(client: &'client ApiClient) -> Self {
-        Self { client }
+impl RuleClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn in_namespace(mut self, namespace_key: &str) -> Self {
+        self.options.namespace_key = Some(namespace_key.into());
+        self
     }
 
     pub async fn list(&self, list: &RuleListRequest) -> Result<RuleList> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules",
-            namespace_key = list
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = list.flag_key
-        );
-        self.client.get(&path, Some(list)).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&list.namespace_key, &self.options),
+            )
+            .flag(&list.flag_key);
+        self.client
+            .transport
+            .get(route, Some(list), &self.options)
+            .await
+    }
+
+    /// Streams the rules matching `list` across all pages, starting
+    /// from its `page_token` and requesting `limit` items per page.
+    pub fn stream(&self, list: &RuleListRequest) -> PageStream<Rule> {
+        let client = self.clone();
+        let namespace_key = list.namespace_key.clone();
+        let flag_key = list.flag_key.clone();
+        let offset = list.offset;
+        PageStream::new(
+            list.page_token.clone(),
+            list.limit,
+            move |page_token, limit| {
+                let client = client.clone();
+                let list = RuleListRequest {
+                    namespace_key: namespace_key.clone(),
+                    flag_key: flag_key.clone(),
+                    offset,
+                    limit,
+                    page_token,
+                };
+                Box::pin(async move {
+                    let res = client.list(&list).await?;
+                    Ok(Page {
+                        items: res.rules,
+                        next_page_token: res.next_page_token,
+                    })
+                })
+            },
+        )
+    }
+
+    /// Fetches all the rules matching `list` across all pages.
+    pub async fn list_all(&self, list: &RuleListRequest) -> Result<Vec<Rule>> {
+        self.stream(list).try_collect().await
     }
 
     pub async fn create(&self, create: &RuleCreateRequest) -> Result<Rule> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules",
-            namespace_key = create
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = create.flag_key
-        );
-        self.client.post(&path, Some(create)).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&create.namespace_key, &self.options),
+            )
+            .flag(&create.flag_key);
+        self.client
+            .transport
+            .post(route, Some(create), &self.options)
+            .await
     }
 
     pub async fn delete(&self, delete: &RuleDeleteRequest) -> Result<RuleDeletion> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{id}",
-            namespace_key = delete
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = delete.flag_key,
-            id = delete.id
-        );
-        self.client.delete(&path, None::<&()>).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{id}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&delete.namespace_key, &self.options),
+            )
+            .flag(&delete.flag_key)
+            .param("id", &delete.id);
+        self.client
+            .transport
+            .delete(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn get(&self, get: &RuleGetRequest) -> Result<Rule> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{id}",
-            namespace_key = get
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = get.flag_key,
-            id = get.id
-        );
-        self.client.get(&path, None::<&()>).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{id}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&get.namespace_key, &self.options),
+            )
+            .flag(&get.flag_key)
+            .param("id", &get.id);
+        self.client
+            .transport
+            .get(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn update(&self, update: &RuleUpdateRequest) -> Result<Rule> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{id}",
-            namespace_key = update
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            flag_key = update.flag_key,
-            id = update.id
-        );
-        self.client.put(&path, Some(update)).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/{id}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&update.namespace_key, &self.options),
+            )
+            .flag(&update.flag_key)
+            .param("id", &update.id);
+        self.client
+            .transport
+            .put(route, Some(update), &self.options)
+            .await
+    }
+
+    /// Sets the order of all the rules of a flag.
+    pub async fn order(&self, order: &RuleOrderRequest) -> Result<RuleOrdering> {
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/flags/{flag_key}/rules/order")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&order.namespace_key, &self.options),
+            )
+            .flag(&order.flag_key);
+        self.client
+            .transport
+            .put(route, Some(order), &self.options)
+            .await
+    }
+
+    /// Moves a rule and returns the rules of the flag in their new order.
+    pub async fn move_to(&self, move_to: &RuleMoveRequest) -> Result<Vec<Rule>> {
+        self.reorder(&move_to.namespace_key, &move_to.flag_key, |ids| {
+            rank::move_to(ids, &move_to.id, move_to.position)
+        })
+        .await
+    }
+
+    /// Swaps two rules and returns the rules of the flag in their new order.
+    pub async fn swap(&self, swap: &RuleSwapRequest) -> Result<Vec<Rule>> {
+        self.reorder(&swap.namespace_key, &swap.flag_key, |ids| {
+            rank::swap(ids, &swap.first, &swap.second)
+        })
+        .await
+    }
+
+    /// Creates a rule at `position` among the rules of its flag. The `rank`
+    /// of `create` is ignored. When the rule cannot be moved into place, it
+    /// is deleted again before the error is returned.
+    pub async fn create_at(&self, create: &RuleCreateRequest, position: Position) -> Result<Rule> {
+        let count = self
+            .list_all(&RuleListRequest {
+                namespace_key: create.namespace_key.clone(),
+                flag_key: create.flag_key.clone(),
+                ..Default::default()
+            })
+            .await?
+            .len();
+        let rule = self
+            .create(&RuleCreateRequest {
+                rank: count + 1,
+                ..create.clone()
+            })
+            .await?;
+
+        let rules = match self
+            .reorder(&create.namespace_key, &create.flag_key, |ids| {
+                rank::move_to(ids, &rule.id, position)
+            })
+            .await
+        {
+            Ok(rules) => rules,
+            Err(err) => {
+                // Leave nothing behind for a caller that retries.
+                let _ = self
+                    .delete(&RuleDeleteRequest {
+                        namespace_key: create.namespace_key.clone(),
+                        flag_key: create.flag_key.clone(),
+                        id: rule.id,
+                    })
+                    .await;
+                return Err(err);
+            }
+        };
+        Ok(rules.into_iter().find(|r| r.id == rule.id).unwrap_or(rule))
+    }
+
+    async fn reorder(
+        &self,
+        namespace_key: &Option<String>,
+        flag_key: &str,
+        change: impl FnOnce(&mut Vec<String>) -> Result<()>,
+    ) -> Result<Vec<Rule>> {
+        let list = RuleListRequest {
+            namespace_key: namespace_key.clone(),
+            flag_key: flag_key.into(),
+            ..Default::default()
+        };
+        rank::reorder(
+            || self.list_all(&list),
+            |rule_ids| async move {
+                self.order(&RuleOrderRequest {
+                    namespace_key: namespace_key.clone(),
+                    flag_key: flag_key.into(),
+                    rule_ids,
+                })
+                .await
+                .map(|_| ())
+            },
+            change,
+        )
+        .await
     }
 }
 
@@ -83,7 +249,7 @@ pub struct RuleGetRequest {
     pub id: String,
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RuleCreateRequest {
     #[serde(skip_serializing)]
@@ -96,13 +262,13 @@ pub struct RuleCreateRequest {
     pub rank: usize,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
-pub enum SegmentOperator {
-    #[default]
-    #[serde(rename = "OR_SEGMENT_OPERATOR")]
-    Or,
-    #[serde(rename = "AND_SEGMENT_OPERATOR")]
-    And,
+wire_enum! {
+    #[derive(Default)]
+    pub enum SegmentOperator {
+        #[default]
+        Or = "OR_SEGMENT_OPERATOR",
+        And = "AND_SEGMENT_OPERATOR",
+    }
 }
 
 #[derive(Debug, Default, Serialize)]
@@ -130,6 +296,35 @@ pub struct RuleDeleteRequest {
 #[derive(Debug, Clone, Deserialize)]
 pub struct RuleDeletion {}
 
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleOrderRequest {
+    #[serde(skip_serializing)]
+    pub namespace_key: Option<String>,
+    #[serde(skip_serializing)]
+    pub flag_key: String,
+    pub rule_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuleOrdering {}
+
+#[derive(Debug)]
+pub struct RuleMoveRequest {
+    pub namespace_key: Option<String>,
+    pub flag_key: String,
+    pub id: String,
+    pub position: Position,
+}
+
+#[derive(Debug, Default)]
+pub struct RuleSwapRequest {
+    pub namespace_key: Option<String>,
+    pub flag_key: String,
+    pub first: String,
+    pub second: String,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RuleListRequest {
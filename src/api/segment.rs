@@ -1,73 +1,135 @@
 use crate::api::constraint::Constraint;
-use crate::api::{ApiClient, Result, DEFAULT_LIMIT, DEFAULT_NAMESPACE};
+use crate::api::{ApiClient, Result, DEFAULT_LIMIT};
+use crate::pagination::{Page, PageStream};
+use crate::transport::{CallOptions, Route};
+use crate::wire::wire_enum;
 use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-pub struct SegmentClient<'client> {
-    client: &'client ApiClient,
+#[derive(Debug, Clone)]
+pub struct SegmentClient {
+    client: ApiClient,
+    options: CallOptions,
 }
 
-impl<'client> SegmentClient<'client> {
-    pub fn new(client: &'client ApiClient) -> Self {
-        Self { client }
+impl SegmentClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn in_namespace(mut self, namespace_key: &str) -> Self {
+        self.options.namespace_key = Some(namespace_key.into());
+        self
     }
 
     pub async fn list(&self, list: &SegmentListRequest) -> Result<SegmentList> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/segments",
-            namespace_key = list
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string())
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/segments").namespace(
+            self.client
+                .transport
+                .namespace_or(&list.namespace_key, &self.options),
         );
-        self.client.get(&path, Some(list)).await
+        self.client
+            .transport
+            .get(route, Some(list), &self.options)
+            .await
+    }
+
+    /// Streams the segments matching `list` across all pages, starting
+    /// from its `page_token` and requesting `limit` items per page.
+    pub fn stream(&self, list: &SegmentListRequest) -> PageStream<Segment> {
+        let client = self.clone();
+        let namespace_key = list.namespace_key.clone();
+        let offset = list.offset;
+        PageStream::new(
+            list.page_token.clone(),
+            list.limit,
+            move |page_token, limit| {
+                let client = client.clone();
+                let list = SegmentListRequest {
+                    namespace_key: namespace_key.clone(),
+                    offset,
+                    limit,
+                    page_token,
+                };
+                Box::pin(async move {
+                    let res = client.list(&list).await?;
+                    Ok(Page {
+                        items: res.segments,
+                        next_page_token: res.next_page_token,
+                    })
+                })
+            },
+        )
+    }
+
+    /// Fetches all the segments matching `list` across all pages.
+    pub async fn list_all(&self, list: &SegmentListRequest) -> Result<Vec<Segment>> {
+        self.stream(list).try_collect().await
     }
 
     pub async fn get(&self, get: &SegmentGetRequest) -> Result<Segment> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/segments/{key}",
-            namespace_key = get
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            key = get.key
-        );
-        self.client.get(&path, None::<&()>).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/segments/{segment_key}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&get.namespace_key, &self.options),
+            )
+            .param("segment_key", &get.key);
+        self.client
+            .transport
+            .get(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn create(&self, create: &SegmentCreateRequest) -> Result<Segment> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/segments",
-            namespace_key = create
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/segments").namespace(
+            self.client
+                .transport
+                .namespace_or(&create.namespace_key, &self.options),
         );
-        self.client.post(&path, Some(create)).await
+        self.client
+            .transport
+            .post(route, Some(create), &self.options)
+            .await
     }
 
     pub async fn delete(&self, delete: &SegmentDeleteRequest) -> Result<SegmentDeletion> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/segments/{key}",
-            namespace_key = delete
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            key = delete.key
-        );
-        self.client.delete(&path, None::<&()>).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/segments/{segment_key}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&delete.namespace_key, &self.options),
+            )
+            .param("segment_key", &delete.key);
+        self.client
+            .transport
+            .delete(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn update(&self, update: &SegmentUpdateRequest) -> Result<Segment> {
-        let path = format!(
-            "/api/v1/namespaces/{namespace_key}/segments/{key}",
-            namespace_key = update
-                .namespace_key
-                .as_ref()
-                .unwrap_or(&DEFAULT_NAMESPACE.to_string()),
-            key = update.key
-        );
-        self.client.put(&path, Some(update)).await
+        let route = Route::new("/api/v1/namespaces/{namespace_key}/segments/{segment_key}")
+            .namespace(
+                self.client
+                    .transport
+                    .namespace_or(&update.namespace_key, &self.options),
+            )
+            .param("segment_key", &update.key);
+        self.client
+            .transport
+            .put(route, Some(update), &self.options)
+            .await
     }
 }
 
@@ -141,13 +203,13 @@ impl SegmentListRequest {
     }
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, Eq, PartialEq)]
-pub enum Match {
-    #[default]
-    #[serde(rename = "ALL_MATCH_TYPE")]
-    All,
-    #[serde(rename = "ANY_MATCH_TYPE")]
-    Any,
+wire_enum! {
+    #[derive(Default)]
+    pub enum Match {
+        #[default]
+        All = "ALL_MATCH_TYPE",
+        Any = "ANY_MATCH_TYPE",
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
@@ -166,7 +228,7 @@ pub struct Segment {
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SegmentList {
-    pub flags: Vec<Segment>,
+    pub segments: Vec<Segment>,
     pub next_page_token: String,
     pub total_count: u32,
 }
@@ -0,0 +1,57 @@
+use crate::auth::{AuthClient, Authentication, Result};
+use crate::transport::{CallOptions, Route};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Where the kubelet mounts the projected service account token.
+pub const DEFAULT_SERVICE_ACCOUNT_TOKEN_PATH: &str =
+    "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+#[derive(Debug, Clone)]
+pub struct KubernetesClient {
+    client: AuthClient,
+    options: CallOptions,
+}
+
+impl KubernetesClient {
+    pub fn new(client: &AuthClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    /// Exchanges a service account token for a Flipt client token.
+    pub async fn verify_service_account(
+        &self,
+        verify: &VerifyServiceAccountRequest,
+    ) -> Result<ServiceAccountVerification> {
+        self.client
+            .transport
+            .post(
+                Route::new("/auth/v1/method/kubernetes/serviceaccount"),
+                Some(verify),
+                &self.options,
+            )
+            .await
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyServiceAccountRequest {
+    pub service_account_token: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceAccountVerification {
+    pub client_token: String,
+    pub authentication: Authentication,
+}
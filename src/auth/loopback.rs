@@ -0,0 +1,149 @@
+//! A single-purpose HTTP listener receiving the browser redirect at the end
+//! of an OIDC or GitHub login.
+
+use crate::error::{Error, Result};
+use futures_util::future::poll_fn;
+use futures_util::stream::FuturesUnordered;
+use futures_util::StreamExt;
+use std::net::SocketAddr;
+use std::task::Poll;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+/// How long `login` waits for the browser redirect unless configured.
+pub(crate) const DEFAULT_LOGIN_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How long a single connection may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SUCCESS_PAGE: &str =
+    "<html><body><p>Logged in to Flipt. You can close this window.</p></body></html>";
+const FAILURE_PAGE: &str =
+    "<html><body><p>Login to Flipt failed. You can close this window.</p></body></html>";
+
+/// Waits on `listener` for the browser redirect and returns its
+/// authorization code, giving up after `timeout`.
+///
+/// Connections are handled concurrently, so one that is slow to send its
+/// request, such as a browser preconnect, does not hold up the redirect.
+/// Requests without a `code` or `error`, such as for a favicon, and requests
+/// that do not carry `state` are answered and otherwise ignored.
+pub(crate) async fn wait_for_redirect(
+    listener: &TcpListener,
+    state: &str,
+    timeout: Duration,
+) -> Result<String> {
+    tokio::time::timeout(timeout, accept_redirect(listener, state))
+        .await
+        .map_err(|_| Error::Login("timed out waiting for the redirect".into()))?
+}
+
+async fn accept_redirect(listener: &TcpListener, state: &str) -> Result<String> {
+    enum Event {
+        Accepted(std::io::Result<(TcpStream, SocketAddr)>),
+        Redirected(Result<String>),
+    }
+
+    let mut connections = FuturesUnordered::new();
+    loop {
+        let event = poll_fn(|cx| {
+            while let Poll::Ready(Some(handled)) = connections.poll_next_unpin(cx) {
+                if let Some(outcome) = handled {
+                    return Poll::Ready(Event::Redirected(outcome));
+                }
+            }
+            listener.poll_accept(cx).map(Event::Accepted)
+        })
+        .await;
+        match event {
+            Event::Accepted(accepted) => {
+                let (stream, _) = accepted.map_err(io_error)?;
+                connections.push(handle(stream, state));
+            }
+            Event::Redirected(outcome) => return outcome,
+        }
+    }
+}
+
+/// Answers one connection. Returns `None` when it is not the redirect for
+/// this login.
+async fn handle(mut stream: TcpStream, state: &str) -> Option<Result<String>> {
+    let target = tokio::time::timeout(READ_TIMEOUT, read_target(&mut stream))
+        .await
+        .ok()?
+        .ok()?;
+    let query = target.split_once('?').map_or("", |(_, q)| q);
+    let param = |name: &str| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.into_owned())
+    };
+
+    if param("code").is_none() && param("error").is_none() {
+        respond(&mut stream, "404 Not Found", "").await;
+        return None;
+    }
+
+    if param("state").as_deref() != Some(state) {
+        respond(&mut stream, "400 Bad Request", FAILURE_PAGE).await;
+        return None;
+    }
+
+    if let Some(error) = param("error") {
+        respond(&mut stream, "400 Bad Request", FAILURE_PAGE).await;
+        let description = param("error_description").unwrap_or_default();
+        return Some(Err(Error::Login(
+            format!("{error} {description}").trim().to_string(),
+        )));
+    }
+
+    respond(&mut stream, "200 OK", SUCCESS_PAGE).await;
+    Some(Ok(param("code").unwrap_or_default()))
+}
+
+pub(crate) async fn bind(addr: SocketAddr) -> Result<TcpListener> {
+    TcpListener::bind(addr).await.map_err(io_error)
+}
+
+/// Reads the request line and headers, returning the request target.
+async fn read_target(stream: &mut TcpStream) -> Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await.map_err(io_error)?;
+        if n == 0 || head.len() + n > MAX_REQUEST_HEAD {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next().unwrap_or_default().split(' ');
+    Ok(request_line.nth(1).unwrap_or_default().to_string())
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    // The browser going away does not affect the login.
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+fn io_error(e: std::io::Error) -> Error {
+    Error::Login(format!("callback listener: {e}"))
+}
+
+/// An unguessable value tying the redirect to the login that started it:
+/// 128 bits from the system random number generator, hex encoded.
+pub(crate) fn random_state() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| Error::Login(format!("failed to generate state: {e}")))?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
@@ -1,11 +1,17 @@
+pub mod github;
+pub mod kubernetes;
+mod loopback;
+pub mod oidc;
 pub mod token;
 
-use crate::error::{Error, UpstreamError};
+use crate::error::Error;
+use crate::transport::{CallOptions, Route, Transport};
+use crate::wire::wire_enum;
 use crate::{AuthScheme, Config};
 use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, COOKIE};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use url::Url;
 
 pub const METADATA_LABEL_NAME: &str = "io.flipt.auth.token.name";
 pub const METADATA_LABEL_DESCRIPTION: &str = "io.flipt.auth.token.description";
@@ -13,101 +19,80 @@ pub const METADATA_LABEL_NAMESPACE: &str = "io.flipt.auth.token.namespace";
 
 const DEFAULT_LIMIT: usize = 100;
 
-pub type Result<T> = anyhow::Result<T>;
+pub use crate::transport::{deserialize, Result};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AuthClient {
-    client: reqwest::Client,
-    auth_scheme: AuthScheme,
-    endpoint: Url,
+    pub(crate) transport: Transport,
 }
 
 impl AuthClient {
     pub fn new(config: Config) -> Result<Self> {
-        let client = reqwest::Client::builder()
-            .user_agent(config.user_agent)
-            .build()?;
-
-        Ok(Self {
-            client,
-            auth_scheme: config.auth_scheme,
-            endpoint: config.endpoint,
-        })
+        Ok(Self::from_transport(Transport::new(config)?))
     }
 
-    pub async fn me(&self) -> Result<Authentication> {
-        self.get("/auth/v1/self", None::<&()>).await
+    pub(crate) fn from_transport(transport: Transport) -> Self {
+        Self { transport }
     }
 
-    pub fn tokens(&self) -> token::TokenClient {
-        token::TokenClient::new(self)
+    /// Replaces the credentials sent by this client and every client sharing
+    /// its connection pool.
+    pub fn set_auth_scheme(&self, v: AuthScheme) {
+        self.transport.set_auth_scheme(v);
     }
 
-    pub(crate) async fn get<P, R>(&self, path: &str, params: Option<&P>) -> Result<R>
-    where
-        P: serde::Serialize,
-        R: serde::de::DeserializeOwned,
-    {
-        let url = self.build_url(path)?;
-        let mut request = self.client.get(url);
-        if let Some(params) = params {
-            request = request.query(params);
-        }
-        let resp = self.send(request).await?;
-        deserialize(resp).await
+    pub async fn me(&self) -> Result<Authentication> {
+        self.transport
+            .get(
+                Route::new("/auth/v1/self"),
+                None::<&()>,
+                &CallOptions::default(),
+            )
+            .await
     }
 
-    pub(crate) async fn post<B, R>(&self, path: &str, body: Option<&B>) -> Result<R>
-    where
-        B: serde::Serialize,
-        R: serde::de::DeserializeOwned,
-    {
-        let url = self.build_url(path)?;
-        let mut request = self.client.post(url);
-        if let Some(body) = body {
-            request = request
-                .header("Content-Type", "application/json")
-                .json(body);
-        }
-        let resp = self.send(request).await?;
-        deserialize(resp).await
+    /// Expires the authentication of the current credentials, logging out
+    /// the session.
+    pub async fn expire_self(
+        &self,
+        expire: &ExpireSelfRequest,
+    ) -> Result<AuthenticationExpiration> {
+        self.transport
+            .put(
+                Route::new("/auth/v1/self/expire"),
+                Some(expire),
+                &CallOptions::default(),
+            )
+            .await
     }
 
-    pub(crate) async fn delete<P, R>(&self, path: &str, params: Option<&P>) -> Result<R>
-    where
-        P: serde::Serialize,
-        R: serde::de::DeserializeOwned,
-    {
-        let url = self.build_url(path)?;
-        let mut request = self.client.delete(url);
-        if let Some(params) = params {
-            request = request.query(params);
-        }
-        let resp = self.send(request).await?;
-        deserialize(resp).await
+    /// Lists the authentication methods of the server and whether they are
+    /// enabled.
+    pub async fn methods(&self) -> Result<MethodList> {
+        self.transport
+            .get(
+                Route::new("/auth/v1/method"),
+                None::<&()>,
+                &CallOptions::default(),
+            )
+            .await
     }
 
-    async fn send(&self, mut request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
-        match self.auth_scheme {
-            AuthScheme::None => {}
-            AuthScheme::BearerToken(ref token) => {
-                request = request.bearer_auth(token);
-            }
-        }
-        Ok(request.send().await?)
+    pub fn tokens(&self) -> token::TokenClient {
+        token::TokenClient::new(self)
     }
 
-    fn build_url(&self, path: &str) -> Result<Url> {
-        Ok(self.endpoint.join(path)?)
+    pub fn kubernetes(&self) -> kubernetes::KubernetesClient {
+        kubernetes::KubernetesClient::new(self)
+    }
+
+    pub fn oidc(&self) -> oidc::OidcClient {
+        oidc::OidcClient::new(self)
     }
-}
 
-pub async fn deserialize<T: serde::de::DeserializeOwned>(resp: reqwest::Response) -> Result<T> {
-    if resp.status().is_success() {
-        return Ok(resp.json::<T>().await?);
+    pub fn github(&self) -> github::GithubClient {
+        github::GithubClient::new(self)
     }
-    let parsed_err = resp.json::<UpstreamError>().await?;
-    Err(anyhow::Error::new(Error::Upstream(parsed_err)))
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
@@ -121,6 +106,21 @@ pub struct Authentication {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Where to send the user to log in with a browser.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizeUrl {
+    pub authorize_url: String,
+}
+
+/// The result of a completed browser login.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Login {
+    pub client_token: String,
+    pub authentication: Authentication,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AuthenticationList {
@@ -128,10 +128,52 @@ pub struct AuthenticationList {
     pub next_page_token: String,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
-pub enum Method {
-    #[serde(rename = "METHOD_NONE")]
-    None,
-    #[serde(rename = "METHOD_TOKEN")]
-    Token,
+wire_enum! {
+    /// An authentication method.
+    pub enum Method {
+        None = "METHOD_NONE",
+        Token = "METHOD_TOKEN",
+        Oidc = "METHOD_OIDC",
+        Kubernetes = "METHOD_KUBERNETES",
+        Github = "METHOD_GITHUB",
+        Jwt = "METHOD_JWT",
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpireSelfRequest {
+    /// When the session expires. Unset expires it immediately.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthenticationExpiration {}
+
+/// An authentication method as configured on the server.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodInfo {
+    pub method: Method,
+    #[serde(default)]
+    pub enabled: bool,
+    /// Whether the method authenticates browser sessions.
+    #[serde(default)]
+    pub session_compatible: bool,
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodList {
+    pub methods: Vec<MethodInfo>,
+}
+
+/// The cookie Flipt checks the callback `state` against.
+fn state_cookie(state: &str) -> Result<HeaderMap> {
+    let value = HeaderValue::from_str(&format!("flipt_client_state={state}"))
+        .map_err(|_| Error::Login("invalid state".into()))?;
+    Ok(HeaderMap::from_iter([(COOKIE, value)]))
 }
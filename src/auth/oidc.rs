@@ -0,0 +1,105 @@
+use crate::auth::{loopback, state_cookie, AuthClient, AuthorizeUrl, Login, Result};
+use crate::transport::{CallOptions, Route};
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct OidcClient {
+    client: AuthClient,
+    options: CallOptions,
+    login_timeout: Duration,
+}
+
+impl OidcClient {
+    pub fn new(client: &AuthClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+            login_timeout: loopback::DEFAULT_LOGIN_TIMEOUT,
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    /// How long `login` waits for the user to finish in the browser.
+    /// Defaults to five minutes.
+    pub fn with_login_timeout(mut self, timeout: Duration) -> Self {
+        self.login_timeout = timeout;
+        self
+    }
+
+    /// The identity provider URL to send the user to.
+    pub async fn authorize_url(&self, authorize: &OidcAuthorizeRequest) -> Result<AuthorizeUrl> {
+        let route = Route::new("/auth/v1/method/oidc/{provider}/authorize")
+            .param("provider", &authorize.provider);
+        self.client
+            .transport
+            .get(route, Some(authorize), &self.options)
+            .await
+    }
+
+    /// Exchanges the code and state the identity provider redirected to for
+    /// a client token.
+    pub async fn callback(&self, callback: &OidcCallbackRequest) -> Result<Login> {
+        let route = Route::new("/auth/v1/method/oidc/{provider}/callback")
+            .param("provider", &callback.provider);
+        let mut options = self.options.clone();
+        options.headers.extend(state_cookie(&callback.state)?);
+        self.client
+            .transport
+            .get(route, Some(callback), &options)
+            .await
+    }
+
+    /// Logs in through a browser. `open` is given the authorize URL to show
+    /// or open for the user, and the redirect is received on
+    /// `redirect_addr`, which must match the redirect address configured for
+    /// the provider in Flipt.
+    pub async fn login<F>(
+        &self,
+        provider: &str,
+        redirect_addr: SocketAddr,
+        open: F,
+    ) -> Result<Login>
+    where
+        F: FnOnce(&str),
+    {
+        let listener = loopback::bind(redirect_addr).await?;
+        let state = loopback::random_state()?;
+        let authorize = self
+            .authorize_url(&OidcAuthorizeRequest {
+                provider: provider.into(),
+                state: state.clone(),
+            })
+            .await?;
+        open(&authorize.authorize_url);
+
+        let code = loopback::wait_for_redirect(&listener, &state, self.login_timeout).await?;
+        self.callback(&OidcCallbackRequest {
+            provider: provider.into(),
+            code,
+            state,
+        })
+        .await
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct OidcAuthorizeRequest {
+    #[serde(skip_serializing)]
+    pub provider: String,
+    pub state: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct OidcCallbackRequest {
+    #[serde(skip_serializing)]
+    pub provider: String,
+    pub code: String,
+    pub state: String,
+}
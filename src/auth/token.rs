@@ -1,34 +1,94 @@
 use crate::auth::{AuthClient, Authentication, AuthenticationList, Result, DEFAULT_LIMIT};
+use crate::pagination::{Page, PageStream};
+use crate::transport::{CallOptions, Route};
 use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-pub struct TokenClient<'client> {
-    client: &'client AuthClient,
+#[derive(Debug, Clone)]
+pub struct TokenClient {
+    client: AuthClient,
+    options: CallOptions,
 }
 
-impl<'client> TokenClient<'client> {
-    pub fn new(client: &'client AuthClient) -> Self {
-        Self { client }
+impl TokenClient {
+    pub fn new(client: &AuthClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
     }
 
     pub async fn list(&self, list: &TokenListRequest) -> Result<AuthenticationList> {
-        self.client.get("/auth/v1/tokens", Some(&list)).await
+        self.client
+            .transport
+            .get(Route::new("/auth/v1/tokens"), Some(&list), &self.options)
+            .await
+    }
+
+    /// Streams the token authentications matching `list` across all pages, starting
+    /// from its `page_token` and requesting `limit` items per page.
+    pub fn stream(&self, list: &TokenListRequest) -> PageStream<Authentication> {
+        let client = self.clone();
+        let offset = list.offset;
+        PageStream::new(
+            list.page_token.clone(),
+            list.limit,
+            move |page_token, limit| {
+                let client = client.clone();
+                let list = TokenListRequest {
+                    offset,
+                    limit,
+                    page_token,
+                };
+                Box::pin(async move {
+                    let res = client.list(&list).await?;
+                    Ok(Page {
+                        items: res.authentications,
+                        next_page_token: res.next_page_token,
+                    })
+                })
+            },
+        )
+    }
+
+    /// Fetches all the token authentications matching `list` across all pages.
+    pub async fn list_all(&self, list: &TokenListRequest) -> Result<Vec<Authentication>> {
+        self.stream(list).try_collect().await
     }
 
     pub async fn get(&self, id: &str) -> Result<Authentication> {
-        let path = format!("/auth/v1/tokens/{id}");
-        self.client.get(&path, None::<&()>).await
+        let route = Route::new("/auth/v1/tokens/{id}").param("id", id);
+        self.client
+            .transport
+            .get(route, None::<&()>, &self.options)
+            .await
     }
 
     pub async fn create(&self, create: &TokenCreateRequest) -> Result<TokenCreation> {
         self.client
-            .post("/auth/v1/method/token", Some(&create))
+            .transport
+            .post(
+                Route::new("/auth/v1/method/token"),
+                Some(&create),
+                &self.options,
+            )
             .await
     }
 
     pub async fn delete(&self, id: &str) -> Result<AuthenticationDeletion> {
-        let path = format!("/auth/v1/tokens/{id}");
-        self.client.delete(&path, None::<&()>).await
+        let route = Route::new("/auth/v1/tokens/{id}").param("id", id);
+        self.client
+            .transport
+            .delete(route, None::<&()>, &self.options)
+            .await
     }
 }
 
@@ -0,0 +1,240 @@
+use crate::api::constraint::{
+    Constraint, ConstraintCreateRequest, ConstraintDeleteRequest, ConstraintDeletion,
+    ConstraintUpdateRequest,
+};
+use crate::api::distribution::{
+    Distribution, DistributionCreateRequest, DistributionDeleteRequest, DistributionDeletion,
+    DistributionUpdateRequest,
+};
+use crate::api::evaluation::{BatchEvaluateRequest, EvaluateRequest, Evaluation};
+use crate::api::flag::{
+    Flag, FlagCreateRequest, FlagDeleteRequest, FlagDeletion, FlagGetRequest, FlagList,
+    FlagListRequest, FlagUpdateRequest,
+};
+use crate::api::namespace::{
+    Namespace, NamespaceCreateRequest, NamespaceDeleteRequest, NamespaceDeletion,
+    NamespaceGetRequest, NamespaceList, NamespaceListRequest, NamespaceUpdateRequest,
+};
+use crate::api::rank::Position;
+use crate::api::rollout::{
+    Empty, Rollout, RolloutCreateRequest, RolloutDeleteRequest, RolloutGetRequest, RolloutList,
+    RolloutListRequest, RolloutMoveRequest, RolloutOrderRequest, RolloutSwapRequest,
+    RolloutUpdateRequest,
+};
+use crate::api::rule::{
+    Rule, RuleCreateRequest, RuleDeleteRequest, RuleDeletion, RuleGetRequest, RuleList,
+    RuleListRequest, RuleMoveRequest, RuleOrderRequest, RuleOrdering, RuleSwapRequest,
+    RuleUpdateRequest,
+};
+use crate::api::segment::{
+    Segment, SegmentCreateRequest, SegmentDeleteRequest, SegmentDeletion, SegmentGetRequest,
+    SegmentList, SegmentListRequest, SegmentUpdateRequest,
+};
+use crate::api::variant::{
+    Variant, VariantCreateRequest, VariantDeleteRequest, VariantDeletion, VariantUpdateRequest,
+};
+use crate::api::{self, constraint, distribution, evaluation, flag, namespace, rollout, rule};
+use crate::api::{segment, variant};
+use crate::blocking::{blocking_client, runtime};
+use crate::error::Result;
+use crate::{AuthScheme, Config};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// Blocking counterpart of [`crate::api::ApiClient`].
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    pub(crate) inner: api::ApiClient,
+    runtime: Arc<Runtime>,
+}
+
+impl ApiClient {
+    pub fn new(config: Config) -> Result<Self> {
+        Ok(Self::from_async(api::ApiClient::new(config)?, runtime()?))
+    }
+
+    pub(crate) fn from_async(inner: api::ApiClient, runtime: Arc<Runtime>) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Replaces the credentials sent by this client and every client sharing
+    /// its connection pool.
+    pub fn set_auth_scheme(&self, v: AuthScheme) {
+        self.inner.set_auth_scheme(v);
+    }
+
+    pub(crate) fn runtime(&self) -> Arc<Runtime> {
+        self.runtime.clone()
+    }
+
+    pub fn flags(&self) -> FlagClient {
+        FlagClient::from_async(self.inner.flags(), self.runtime.clone())
+    }
+
+    pub fn variants(&self) -> VariantClient {
+        VariantClient::from_async(self.inner.variants(), self.runtime.clone())
+    }
+
+    pub fn segments(&self) -> SegmentClient {
+        SegmentClient::from_async(self.inner.segments(), self.runtime.clone())
+    }
+
+    pub fn constraints(&self) -> ConstraintClient {
+        ConstraintClient::from_async(self.inner.constraints(), self.runtime.clone())
+    }
+
+    pub fn distributions(&self) -> DistributionClient {
+        DistributionClient::from_async(self.inner.distributions(), self.runtime.clone())
+    }
+
+    pub fn rollouts(&self) -> RolloutClient {
+        RolloutClient::from_async(self.inner.rollouts(), self.runtime.clone())
+    }
+
+    pub fn rules(&self) -> RuleClient {
+        RuleClient::from_async(self.inner.rules(), self.runtime.clone())
+    }
+
+    pub fn evaluation(&self) -> EvaluationClient {
+        EvaluationClient::from_async(self.inner.evaluation(), self.runtime.clone())
+    }
+
+    pub fn namespaces(&self) -> NamespaceClient {
+        NamespaceClient::from_async(self.inner.namespaces(), self.runtime.clone())
+    }
+
+    /// Sub-clients whose requests default to `namespace_key` instead of the
+    /// configured namespace.
+    pub fn namespace(&self, namespace_key: &str) -> NamespacedClient {
+        NamespacedClient {
+            inner: self.inner.namespace(namespace_key),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking counterpart of [`crate::api::NamespacedClient`].
+#[derive(Debug)]
+pub struct NamespacedClient {
+    inner: api::NamespacedClient,
+    runtime: Arc<Runtime>,
+}
+
+impl NamespacedClient {
+    pub fn flags(&self) -> FlagClient {
+        FlagClient::from_async(self.inner.flags(), self.runtime.clone())
+    }
+
+    pub fn variants(&self) -> VariantClient {
+        VariantClient::from_async(self.inner.variants(), self.runtime.clone())
+    }
+
+    pub fn segments(&self) -> SegmentClient {
+        SegmentClient::from_async(self.inner.segments(), self.runtime.clone())
+    }
+
+    pub fn constraints(&self) -> ConstraintClient {
+        ConstraintClient::from_async(self.inner.constraints(), self.runtime.clone())
+    }
+
+    pub fn distributions(&self) -> DistributionClient {
+        DistributionClient::from_async(self.inner.distributions(), self.runtime.clone())
+    }
+
+    pub fn rollouts(&self) -> RolloutClient {
+        RolloutClient::from_async(self.inner.rollouts(), self.runtime.clone())
+    }
+
+    pub fn rules(&self) -> RuleClient {
+        RuleClient::from_async(self.inner.rules(), self.runtime.clone())
+    }
+
+    pub fn evaluation(&self) -> crate::blocking::EvaluationClient {
+        crate::blocking::EvaluationClient::from_async(self.inner.evaluation(), self.runtime.clone())
+    }
+}
+
+blocking_client! {
+    pub struct FlagClient => flag::FlagClient;
+    pub fn list(&self, list: &FlagListRequest) -> FlagList;
+    pub fn list_all(&self, list: &FlagListRequest) -> Vec<Flag>;
+    pub fn get(&self, get: &FlagGetRequest) -> Flag;
+    pub fn create(&self, create: &FlagCreateRequest) -> Flag;
+    pub fn delete(&self, delete: &FlagDeleteRequest) -> FlagDeletion;
+    pub fn update(&self, update: &FlagUpdateRequest) -> Flag;
+}
+
+blocking_client! {
+    pub struct VariantClient => variant::VariantClient;
+    pub fn create(&self, create: &VariantCreateRequest) -> Variant;
+    pub fn delete(&self, delete: &VariantDeleteRequest) -> VariantDeletion;
+    pub fn update(&self, update: &VariantUpdateRequest) -> Variant;
+}
+
+blocking_client! {
+    pub struct SegmentClient => segment::SegmentClient;
+    pub fn list(&self, list: &SegmentListRequest) -> SegmentList;
+    pub fn list_all(&self, list: &SegmentListRequest) -> Vec<Segment>;
+    pub fn get(&self, get: &SegmentGetRequest) -> Segment;
+    pub fn create(&self, create: &SegmentCreateRequest) -> Segment;
+    pub fn delete(&self, delete: &SegmentDeleteRequest) -> SegmentDeletion;
+    pub fn update(&self, update: &SegmentUpdateRequest) -> Segment;
+}
+
+blocking_client! {
+    pub struct ConstraintClient => constraint::ConstraintClient;
+    pub fn create(&self, create: &ConstraintCreateRequest) -> Constraint;
+    pub fn delete(&self, delete: &ConstraintDeleteRequest) -> ConstraintDeletion;
+    pub fn update(&self, update: &ConstraintUpdateRequest) -> Constraint;
+}
+
+blocking_client! {
+    pub struct DistributionClient => distribution::DistributionClient;
+    pub fn create(&self, create: &DistributionCreateRequest) -> Distribution;
+    pub fn delete(&self, delete: &DistributionDeleteRequest) -> DistributionDeletion;
+    pub fn update(&self, update: &DistributionUpdateRequest) -> Distribution;
+}
+
+blocking_client! {
+    pub struct RolloutClient => rollout::RolloutClient;
+    pub fn list(&self, list: &RolloutListRequest) -> RolloutList;
+    pub fn list_all(&self, list: &RolloutListRequest) -> Vec<Rollout>;
+    pub fn get(&self, get: &RolloutGetRequest) -> Rollout;
+    pub fn create(&self, create: &RolloutCreateRequest) -> Rollout;
+    pub fn delete(&self, delete: &RolloutDeleteRequest) -> Empty;
+    pub fn update(&self, update: &RolloutUpdateRequest) -> Rollout;
+    pub fn order(&self, order: &RolloutOrderRequest) -> Empty;
+    pub fn move_to(&self, move_to: &RolloutMoveRequest) -> Vec<Rollout>;
+    pub fn swap(&self, swap: &RolloutSwapRequest) -> Vec<Rollout>;
+    pub fn create_at(&self, create: &RolloutCreateRequest, position: Position) -> Rollout;
+}
+
+blocking_client! {
+    pub struct RuleClient => rule::RuleClient;
+    pub fn list(&self, list: &RuleListRequest) -> RuleList;
+    pub fn list_all(&self, list: &RuleListRequest) -> Vec<Rule>;
+    pub fn create(&self, create: &RuleCreateRequest) -> Rule;
+    pub fn delete(&self, delete: &RuleDeleteRequest) -> RuleDeletion;
+    pub fn get(&self, get: &RuleGetRequest) -> Rule;
+    pub fn update(&self, update: &RuleUpdateRequest) -> Rule;
+    pub fn order(&self, order: &RuleOrderRequest) -> RuleOrdering;
+    pub fn move_to(&self, move_to: &RuleMoveRequest) -> Vec<Rule>;
+    pub fn swap(&self, swap: &RuleSwapRequest) -> Vec<Rule>;
+    pub fn create_at(&self, create: &RuleCreateRequest, position: Position) -> Rule;
+}
+
+blocking_client! {
+    pub struct EvaluationClient => evaluation::EvaluationClient;
+    pub fn evaluate(&self, eval: &EvaluateRequest) -> Evaluation;
+    pub fn evaluate_batch(&self, batch: &BatchEvaluateRequest) -> Evaluation;
+}
+
+blocking_client! {
+    pub struct NamespaceClient => namespace::NamespaceClient;
+    pub fn list(&self, list: &NamespaceListRequest) -> NamespaceList;
+    pub fn list_all(&self, list: &NamespaceListRequest) -> Vec<Namespace>;
+    pub fn get(&self, get: &NamespaceGetRequest) -> Namespace;
+    pub fn create(&self, create: &NamespaceCreateRequest) -> Namespace;
+    pub fn delete(&self, delete: &NamespaceDeleteRequest) -> NamespaceDeletion;
+    pub fn update(&self, update: &NamespaceUpdateRequest) -> Namespace;
+}
@@ -0,0 +1,130 @@
+use crate::auth::github::{self, GithubAuthorizeRequest, GithubCallbackRequest};
+use crate::auth::kubernetes::{self, ServiceAccountVerification, VerifyServiceAccountRequest};
+use crate::auth::oidc::{self, OidcAuthorizeRequest, OidcCallbackRequest};
+use crate::auth::token::{
+    self, AuthenticationDeletion, TokenCreateRequest, TokenCreation, TokenListRequest,
+};
+use crate::auth::{
+    self, Authentication, AuthenticationExpiration, AuthenticationList, AuthorizeUrl,
+    ExpireSelfRequest, Login, MethodList,
+};
+use crate::blocking::{blocking_client, runtime};
+use crate::error::Result;
+use crate::{AuthScheme, Config};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// Blocking counterpart of [`crate::auth::AuthClient`].
+#[derive(Debug, Clone)]
+pub struct AuthClient {
+    inner: auth::AuthClient,
+    runtime: Arc<Runtime>,
+}
+
+impl AuthClient {
+    pub fn new(config: Config) -> Result<Self> {
+        Ok(Self::from_async(auth::AuthClient::new(config)?, runtime()?))
+    }
+
+    pub(crate) fn from_async(inner: auth::AuthClient, runtime: Arc<Runtime>) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Replaces the credentials sent by this client and every client sharing
+    /// its connection pool.
+    pub fn set_auth_scheme(&self, v: AuthScheme) {
+        self.inner.set_auth_scheme(v);
+    }
+
+    pub fn me(&self) -> Result<Authentication> {
+        self.runtime.block_on(self.inner.me())
+    }
+
+    pub fn expire_self(&self, expire: &ExpireSelfRequest) -> Result<AuthenticationExpiration> {
+        self.runtime.block_on(self.inner.expire_self(expire))
+    }
+
+    pub fn methods(&self) -> Result<MethodList> {
+        self.runtime.block_on(self.inner.methods())
+    }
+
+    pub fn tokens(&self) -> TokenClient {
+        TokenClient::from_async(self.inner.tokens(), self.runtime.clone())
+    }
+
+    pub fn kubernetes(&self) -> KubernetesClient {
+        KubernetesClient::from_async(self.inner.kubernetes(), self.runtime.clone())
+    }
+
+    pub fn oidc(&self) -> OidcClient {
+        OidcClient::from_async(self.inner.oidc(), self.runtime.clone())
+    }
+
+    pub fn github(&self) -> GithubClient {
+        GithubClient::from_async(self.inner.github(), self.runtime.clone())
+    }
+}
+
+blocking_client! {
+    pub struct TokenClient => token::TokenClient;
+    pub fn list(&self, list: &TokenListRequest) -> AuthenticationList;
+    pub fn list_all(&self, list: &TokenListRequest) -> Vec<Authentication>;
+    pub fn get(&self, id: &str) -> Authentication;
+    pub fn create(&self, create: &TokenCreateRequest) -> TokenCreation;
+    pub fn delete(&self, id: &str) -> AuthenticationDeletion;
+}
+
+blocking_client! {
+    pub struct KubernetesClient => kubernetes::KubernetesClient;
+    pub fn verify_service_account(
+        &self,
+        verify: &VerifyServiceAccountRequest
+    ) -> ServiceAccountVerification;
+}
+
+blocking_client! {
+    pub struct OidcClient => oidc::OidcClient;
+    pub fn authorize_url(&self, authorize: &OidcAuthorizeRequest) -> AuthorizeUrl;
+    pub fn callback(&self, callback: &OidcCallbackRequest) -> Login;
+}
+
+impl OidcClient {
+    /// See [`crate::auth::oidc::OidcClient::with_login_timeout`].
+    pub fn with_login_timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.with_login_timeout(timeout);
+        self
+    }
+
+    /// See [`crate::auth::oidc::OidcClient::login`].
+    pub fn login<F>(&self, provider: &str, redirect_addr: SocketAddr, open: F) -> Result<Login>
+    where
+        F: FnOnce(&str),
+    {
+        self.runtime
+            .block_on(self.inner.login(provider, redirect_addr, open))
+    }
+}
+
+blocking_client! {
+    pub struct GithubClient => github::GithubClient;
+    pub fn authorize_url(&self, authorize: &GithubAuthorizeRequest) -> AuthorizeUrl;
+    pub fn callback(&self, callback: &GithubCallbackRequest) -> Login;
+}
+
+impl GithubClient {
+    /// See [`crate::auth::github::GithubClient::with_login_timeout`].
+    pub fn with_login_timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.with_login_timeout(timeout);
+        self
+    }
+
+    /// See [`crate::auth::github::GithubClient::login`].
+    pub fn login<F>(&self, redirect_addr: SocketAddr, open: F) -> Result<Login>
+    where
+        F: FnOnce(&str),
+    {
+        self.runtime.block_on(self.inner.login(redirect_addr, open))
+    }
+}
@@ -0,0 +1,22 @@
+use crate::blocking::{blocking_client, ApiClient};
+use crate::evaluation::{
+    self, BatchEvaluateRequest, BatchEvaluation, BooleanEvaluation, EvaluateRequest,
+    VariantEvaluation,
+};
+
+blocking_client! {
+    /// Blocking counterpart of [`crate::evaluation::EvaluationClient`].
+    pub struct EvaluationClient => evaluation::EvaluationClient;
+    pub fn boolean(&self, eval: &EvaluateRequest) -> BooleanEvaluation;
+    pub fn variant(&self, eval: &EvaluateRequest) -> VariantEvaluation;
+    pub fn batch(&self, batch: &BatchEvaluateRequest) -> BatchEvaluation;
+}
+
+impl EvaluationClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self::from_async(
+            evaluation::EvaluationClient::new(&client.inner),
+            client.runtime(),
+        )
+    }
+}
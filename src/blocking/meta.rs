@@ -0,0 +1,38 @@
+use crate::blocking::{blocking_client, runtime};
+use crate::error::Result;
+use crate::meta::{self, info};
+use crate::{AuthScheme, Config};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// Blocking counterpart of [`crate::meta::MetaClient`].
+#[derive(Debug, Clone)]
+pub struct MetaClient {
+    inner: meta::MetaClient,
+    runtime: Arc<Runtime>,
+}
+
+impl MetaClient {
+    pub fn new(config: Config) -> Result<Self> {
+        Ok(Self::from_async(meta::MetaClient::new(config)?, runtime()?))
+    }
+
+    pub(crate) fn from_async(inner: meta::MetaClient, runtime: Arc<Runtime>) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Replaces the credentials sent by this client and every client sharing
+    /// its connection pool.
+    pub fn set_auth_scheme(&self, v: AuthScheme) {
+        self.inner.set_auth_scheme(v);
+    }
+
+    pub fn info(&self) -> InfoClient {
+        InfoClient::from_async(self.inner.info(), self.runtime.clone())
+    }
+}
+
+blocking_client! {
+    pub struct InfoClient => info::InfoClient;
+    pub fn get(&self) -> String;
+}
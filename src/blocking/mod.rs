@@ -0,0 +1,130 @@
+//! Synchronous clients for programs that do not run an async runtime.
+//!
+//! Each client wraps its async counterpart and drives it on a private tokio
+//! runtime with one background worker thread, so requests, responses,
+//! errors, retries and middlewares behave exactly as they do for the async
+//! clients, and pooled connections are kept up between calls. These clients
+//! must not be used from within an async runtime.
+
+pub mod api;
+pub mod auth;
+pub mod evaluation;
+pub mod meta;
+
+pub use api::ApiClient;
+pub use auth::AuthClient;
+pub use evaluation::EvaluationClient;
+pub use meta::MetaClient;
+
+use crate::error::{Error, Result};
+use crate::transport::Transport;
+use crate::{AuthScheme, Config};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// Generates a blocking wrapper around an async sub-client, forwarding
+/// `with_timeout` and every listed method.
+macro_rules! blocking_client {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident => $($inner:ident)::+;
+        $(pub fn $method:ident(&self $(, $arg:ident: $arg_ty:ty)*) -> $ret:ty;)*
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            inner: $($inner)::+,
+            runtime: std::sync::Arc<tokio::runtime::Runtime>,
+        }
+
+        impl $name {
+            pub(crate) fn from_async(
+                inner: $($inner)::+,
+                runtime: std::sync::Arc<tokio::runtime::Runtime>,
+            ) -> Self {
+                Self { inner, runtime }
+            }
+
+            /// Overrides the configured request timeout for calls made through this client.
+            pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+                self.inner = self.inner.with_timeout(timeout);
+                self
+            }
+
+            $(
+                pub fn $method(&self $(, $arg: $arg_ty)*) -> crate::error::Result<$ret> {
+                    self.runtime.block_on(self.inner.$method($($arg),*))
+                }
+            )*
+        }
+    };
+}
+
+pub(crate) use blocking_client;
+
+/// Blocking counterpart of [`crate::FliptClient`].
+#[derive(Debug, Clone)]
+pub struct FliptClient {
+    api: ApiClient,
+    auth: AuthClient,
+    meta: MetaClient,
+}
+
+impl FliptClient {
+    pub fn new(config: Config) -> Result<Self> {
+        let transport = Transport::new(config)?;
+        let runtime = runtime()?;
+
+        Ok(Self {
+            api: ApiClient::from_async(
+                crate::api::ApiClient::from_transport(transport.clone()),
+                runtime.clone(),
+            ),
+            auth: AuthClient::from_async(
+                crate::auth::AuthClient::from_transport(transport.clone()),
+                runtime.clone(),
+            ),
+            meta: MetaClient::from_async(
+                crate::meta::MetaClient::from_transport(transport),
+                runtime,
+            ),
+        })
+    }
+
+    /// Replaces the credentials sent by this client and every client sharing
+    /// its connection pool.
+    pub fn set_auth_scheme(&self, v: AuthScheme) {
+        self.api.set_auth_scheme(v);
+    }
+
+    pub fn api(&self) -> &ApiClient {
+        &self.api
+    }
+
+    pub fn auth(&self) -> &AuthClient {
+        &self.auth
+    }
+
+    pub fn meta(&self) -> &MetaClient {
+        &self.meta
+    }
+
+    pub fn evaluation(&self) -> EvaluationClient {
+        EvaluationClient::new(&self.api)
+    }
+
+    /// Shorthand for `api().namespace(namespace_key)`.
+    pub fn namespace(&self, namespace_key: &str) -> api::NamespacedClient {
+        self.api.namespace(namespace_key)
+    }
+}
+
+pub(crate) fn runtime() -> Result<Arc<Runtime>> {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("flipt-blocking")
+        .enable_all()
+        .build()
+        .map(Arc::new)
+        .map_err(|e| Error::Internal(format!("failed to start runtime: {e}")))
+}
@@ -0,0 +1,56 @@
+use crate::api::{ApiClient, NamespacedClient};
+use crate::auth::AuthClient;
+use crate::evaluation::EvaluationClient;
+use crate::meta::MetaClient;
+use crate::transport::{Result, Transport};
+use crate::{AuthScheme, Config};
+
+/// Entry point for every Flipt endpoint family.
+///
+/// The `api`, `auth`, `meta` and `evaluation` views all share one transport,
+/// so they share a single connection pool and the same request handling.
+#[derive(Debug, Clone)]
+pub struct FliptClient {
+    api: ApiClient,
+    auth: AuthClient,
+    meta: MetaClient,
+}
+
+impl FliptClient {
+    pub fn new(config: Config) -> Result<Self> {
+        let transport = Transport::new(config)?;
+
+        Ok(Self {
+            api: ApiClient::from_transport(transport.clone()),
+            auth: AuthClient::from_transport(transport.clone()),
+            meta: MetaClient::from_transport(transport),
+        })
+    }
+
+    /// Replaces the credentials sent by this client and every client sharing
+    /// its connection pool.
+    pub fn set_auth_scheme(&self, v: AuthScheme) {
+        self.api.set_auth_scheme(v);
+    }
+
+    pub fn api(&self) -> &ApiClient {
+        &self.api
+    }
+
+    pub fn auth(&self) -> &AuthClient {
+        &self.auth
+    }
+
+    pub fn meta(&self) -> &MetaClient {
+        &self.meta
+    }
+
+    pub fn evaluation(&self) -> EvaluationClient {
+        EvaluationClient::new(&self.api)
+    }
+
+    /// Shorthand for `api().namespace(namespace_key)`.
+    pub fn namespace(&self, namespace_key: &str) -> NamespacedClient {
+        self.api.namespace(namespace_key)
+    }
+}
@@ -0,0 +1,672 @@
+//! Layered configuration.
+//!
+//! [`ConfigBuilder`] merges settings from, in increasing priority:
+//!
+//! 1. built-in defaults,
+//! 2. a TOML or YAML config file (`.toml`, `.yaml` or `.yml`), given with
+//!    `ConfigBuilder::set_file` or the `FLIPT_CONFIG_FILE` variable,
+//! 3. `FLIPT_*` environment variables,
+//! 4. values set in code on the builder.
+//!
+//! Every layer is validated and all problems are reported together in a
+//! single [`Error::Config`].
+//!
+//! Config files are read with the `config_file` cargo feature, which is
+//! enabled by default. Without it, setting `FLIPT_CONFIG_FILE` is an error.
+//!
+//! A config file uses the same names as the environment variables, grouped
+//! into sections:
+//!
+//! ```toml
+//! endpoint = "https://flipt.internal"
+//! namespace = "payments"
+//! request_timeout = "5s"
+//!
+//! [auth]
+//! token = "secret"
+//!
+//! [retry]
+//! max_attempts = 5
+//! base_backoff = "200ms"
+//!
+//! [tls]
+//! ca_cert_file = "/etc/flipt/ca.pem"
+//! ```
+//!
+//! | Setting                  | Environment variable         |
+//! |--------------------------|------------------------------|
+//! | `endpoint`               | `FLIPT_ENDPOINT`             |
+//! | `auth.token`             | `FLIPT_AUTH_TOKEN`           |
+//! | `auth.jwt`               | `FLIPT_AUTH_JWT`             |
+//! | `user_agent`             | `FLIPT_USER_AGENT`           |
+//! | `namespace`              | `FLIPT_NAMESPACE`            |
+//! | `connect_timeout`        | `FLIPT_CONNECT_TIMEOUT`      |
+//! | `read_timeout`           | `FLIPT_READ_TIMEOUT`         |
+//! | `request_timeout`        | `FLIPT_REQUEST_TIMEOUT`      |
+//! | `retry.max_attempts`     | `FLIPT_RETRY_MAX_ATTEMPTS`   |
+//! | `retry.base_backoff`     | `FLIPT_RETRY_BASE_BACKOFF`   |
+//! | `retry.max_backoff`      | `FLIPT_RETRY_MAX_BACKOFF`    |
+//! | `retry.jitter`           | `FLIPT_RETRY_JITTER`         |
+//! | `tls.ca_cert_file`       | `FLIPT_CA_CERT_FILE`         |
+//! | `tls.client_cert_file`   | `FLIPT_CLIENT_CERT_FILE`     |
+//! | `tls.client_key_file`    | `FLIPT_CLIENT_KEY_FILE`      |
+//! | `tls.insecure_skip_verify` | `FLIPT_INSECURE_SKIP_VERIFY` |
+//! | `http.gzip`              | `FLIPT_HTTP_GZIP`            |
+//! | `http.brotli`            | `FLIPT_HTTP_BROTLI`          |
+//! | `http.version`           | `FLIPT_HTTP_VERSION`         |
+//! | `http.pool_idle_timeout` | `FLIPT_HTTP_POOL_IDLE_TIMEOUT` |
+//! | `http.pool_max_idle_per_host` | `FLIPT_HTTP_POOL_MAX_IDLE_PER_HOST` |
+//! | `http.tcp_keepalive`     | `FLIPT_HTTP_TCP_KEEPALIVE`   |
+//!
+//! Durations are written like `500ms`, `5s`, `1m` or `1h`; a bare number is
+//! a number of seconds. `http.version` is one of `auto`, `http1` or
+//! `http2_prior_knowledge`.
+
+use crate::error::{Error, Result};
+use crate::middleware::Middleware;
+use crate::retry::RetryPolicy;
+use crate::tls::{Pem, TlsConfig};
+use crate::{parse_duration, AuthScheme, Config, HttpVersion};
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// Builds a [`Config`] from defaults, a config file, the environment and
+/// code overrides. See the [module documentation](self) for details.
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    #[cfg(feature = "config_file")]
+    file: Option<PathBuf>,
+    env: bool,
+    require_endpoint: bool,
+    overrides: Layer,
+    retry_policy: Option<RetryPolicy>,
+    tls: Option<TlsConfig>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+}
+
+impl ConfigBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            #[cfg(feature = "config_file")]
+            file: None,
+            env: true,
+            require_endpoint: false,
+            overrides: Layer::default(),
+            retry_policy: None,
+            tls: None,
+            middlewares: Vec::new(),
+        }
+    }
+
+    /// Reads settings from a TOML or YAML file, chosen by its extension.
+    /// Takes precedence over `FLIPT_CONFIG_FILE`.
+    #[cfg(feature = "config_file")]
+    pub fn set_file(mut self, v: impl Into<PathBuf>) -> Self {
+        self.file = Some(v.into());
+        self
+    }
+
+    /// Whether `FLIPT_*` environment variables are read. Enabled by default.
+    pub fn set_env(mut self, v: bool) -> Self {
+        self.env = v;
+        self
+    }
+
+    /// Makes a missing endpoint an error instead of falling back to
+    /// `http://localhost:8080`.
+    pub(crate) fn require_endpoint(mut self) -> Self {
+        self.require_endpoint = true;
+        self
+    }
+
+    pub fn set_endpoint(mut self, v: Url) -> Self {
+        self.overrides.endpoint = Some(v);
+        self
+    }
+
+    pub fn set_auth_scheme(mut self, v: AuthScheme) -> Self {
+        self.overrides.auth_scheme = Some(v);
+        self
+    }
+
+    pub fn set_user_agent(mut self, v: &str) -> Self {
+        self.overrides.user_agent = Some(v.into());
+        self
+    }
+
+    /// Namespace used by requests that do not set one.
+    pub fn set_namespace(mut self, v: &str) -> Self {
+        self.overrides.namespace = Some(v.into());
+        self
+    }
+
+    pub fn set_connect_timeout(mut self, v: Duration) -> Self {
+        self.overrides.connect_timeout = Some(v);
+        self
+    }
+
+    pub fn set_read_timeout(mut self, v: Duration) -> Self {
+        self.overrides.read_timeout = Some(v);
+        self
+    }
+
+    pub fn set_request_timeout(mut self, v: Duration) -> Self {
+        self.overrides.request_timeout = Some(v);
+        self
+    }
+
+    pub fn set_gzip(mut self, v: bool) -> Self {
+        self.overrides.gzip = Some(v);
+        self
+    }
+
+    pub fn set_brotli(mut self, v: bool) -> Self {
+        self.overrides.brotli = Some(v);
+        self
+    }
+
+    pub fn set_http_version(mut self, v: HttpVersion) -> Self {
+        self.overrides.http_version = Some(v);
+        self
+    }
+
+    pub fn set_pool_idle_timeout(mut self, v: Duration) -> Self {
+        self.overrides.pool_idle_timeout = Some(v);
+        self
+    }
+
+    pub fn set_pool_max_idle_per_host(mut self, v: usize) -> Self {
+        self.overrides.pool_max_idle_per_host = Some(v);
+        self
+    }
+
+    pub fn set_tcp_keepalive(mut self, v: Duration) -> Self {
+        self.overrides.tcp_keepalive = Some(v);
+        self
+    }
+
+    /// Replaces the retry policy built from the file and environment.
+    pub fn set_retry_policy(mut self, v: RetryPolicy) -> Self {
+        self.retry_policy = Some(v);
+        self
+    }
+
+    /// Replaces the TLS settings built from the file and environment.
+    pub fn set_tls(mut self, v: TlsConfig) -> Self {
+        self.tls = Some(v);
+        self
+    }
+
+    pub fn add_middleware<M: Middleware>(mut self, v: M) -> Self {
+        self.middlewares.push(Arc::new(v));
+        self
+    }
+
+    pub fn build(self) -> Result<Config> {
+        let mut errors = Vec::new();
+        let mut layer = Layer::default();
+
+        let env_file = self
+            .env
+            .then(|| env::var_os("FLIPT_CONFIG_FILE"))
+            .flatten()
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from);
+        #[cfg(feature = "config_file")]
+        if let Some(path) = self.file.clone().or(env_file) {
+            match read_file(&path) {
+                Ok(settings) => layer = layer.merge(settings.into_layer(&path, &mut errors)),
+                Err(e) => errors.push(e),
+            }
+        }
+        #[cfg(not(feature = "config_file"))]
+        if env_file.is_some() {
+            errors.push("FLIPT_CONFIG_FILE requires the config_file feature".into());
+        }
+        if self.env {
+            layer =
+                layer.merge(Settings::from_env().into_layer(Path::new("environment"), &mut errors));
+        }
+        let layer = layer.merge(self.overrides);
+        if self.require_endpoint && layer.endpoint.is_none() {
+            errors.push("no endpoint is set, such as with FLIPT_ENDPOINT".into());
+        }
+
+        let mut config = layer.validate(&mut errors);
+        if !errors.is_empty() {
+            return Err(Error::Config(match errors.len() {
+                1 => errors.remove(0),
+                n => format!("{n} problems:\n- {}", errors.join("\n- ")),
+            }));
+        }
+
+        if let Some(retry_policy) = self.retry_policy {
+            config.retry_policy = retry_policy;
+        }
+        if let Some(tls) = self.tls {
+            config.tls = tls;
+        }
+        config.middlewares.extend(self.middlewares);
+        Ok(config)
+    }
+}
+
+/// Raw settings as written in a config file or environment variables.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Settings {
+    endpoint: Option<String>,
+    auth: AuthSettings,
+    user_agent: Option<String>,
+    namespace: Option<String>,
+    connect_timeout: Option<Value>,
+    read_timeout: Option<Value>,
+    request_timeout: Option<Value>,
+    retry: RetrySettings,
+    tls: TlsSettings,
+    http: HttpSettings,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct AuthSettings {
+    token: Option<String>,
+    jwt: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RetrySettings {
+    max_attempts: Option<Value>,
+    base_backoff: Option<Value>,
+    max_backoff: Option<Value>,
+    jitter: Option<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TlsSettings {
+    ca_cert_file: Option<PathBuf>,
+    client_cert_file: Option<PathBuf>,
+    client_key_file: Option<PathBuf>,
+    insecure_skip_verify: Option<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct HttpSettings {
+    gzip: Option<Value>,
+    brotli: Option<Value>,
+    version: Option<Value>,
+    pool_idle_timeout: Option<Value>,
+    pool_max_idle_per_host: Option<Value>,
+    tcp_keepalive: Option<Value>,
+}
+
+/// A scalar that may be written as a string, a number or a boolean.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Value {
+    Bool(bool),
+    Number(u64),
+    String(String),
+}
+
+impl Value {
+    fn duration(&self) -> Option<Duration> {
+        match self {
+            Value::Number(n) => Some(Duration::from_secs(*n)),
+            Value::String(s) => parse_duration(s),
+            Value::Bool(_) => None,
+        }
+    }
+
+    fn number(&self) -> Option<u32> {
+        match self {
+            Value::Number(n) => u32::try_from(*n).ok(),
+            Value::String(s) => s.trim().parse().ok(),
+            Value::Bool(_) => None,
+        }
+    }
+
+    fn boolean(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            Value::String(s) => match s.trim() {
+                "1" | "true" => Some(true),
+                "0" | "false" => Some(false),
+                _ => None,
+            },
+            Value::Number(_) => None,
+        }
+    }
+
+    fn http_version(&self) -> Option<HttpVersion> {
+        match self {
+            Value::String(s) => match s.trim() {
+                "auto" => Some(HttpVersion::Auto),
+                "http1" => Some(HttpVersion::Http1Only),
+                "http2_prior_knowledge" => Some(HttpVersion::Http2PriorKnowledge),
+                _ => None,
+            },
+            Value::Bool(_) | Value::Number(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Number(n) => write!(f, "{n}"),
+            Value::String(s) => write!(f, "{s:?}"),
+        }
+    }
+}
+
+impl Settings {
+    fn from_env() -> Self {
+        let var = |key: &str| env::var(key).ok().filter(|v| !v.is_empty());
+        let value = |key: &str| var(key).map(Value::String);
+        let path = |key: &str| var(key).map(PathBuf::from);
+
+        Self {
+            endpoint: var("FLIPT_ENDPOINT"),
+            auth: AuthSettings {
+                token: var("FLIPT_AUTH_TOKEN"),
+                jwt: var("FLIPT_AUTH_JWT"),
+            },
+            user_agent: var("FLIPT_USER_AGENT"),
+            namespace: var("FLIPT_NAMESPACE"),
+            connect_timeout: value("FLIPT_CONNECT_TIMEOUT"),
+            read_timeout: value("FLIPT_READ_TIMEOUT"),
+            request_timeout: value("FLIPT_REQUEST_TIMEOUT"),
+            retry: RetrySettings {
+                max_attempts: value("FLIPT_RETRY_MAX_ATTEMPTS"),
+                base_backoff: value("FLIPT_RETRY_BASE_BACKOFF"),
+                max_backoff: value("FLIPT_RETRY_MAX_BACKOFF"),
+                jitter: value("FLIPT_RETRY_JITTER"),
+            },
+            tls: TlsSettings {
+                ca_cert_file: path("FLIPT_CA_CERT_FILE"),
+                client_cert_file: path("FLIPT_CLIENT_CERT_FILE"),
+                client_key_file: path("FLIPT_CLIENT_KEY_FILE"),
+                insecure_skip_verify: value("FLIPT_INSECURE_SKIP_VERIFY"),
+            },
+            http: HttpSettings {
+                gzip: value("FLIPT_HTTP_GZIP"),
+                brotli: value("FLIPT_HTTP_BROTLI"),
+                version: value("FLIPT_HTTP_VERSION"),
+                pool_idle_timeout: value("FLIPT_HTTP_POOL_IDLE_TIMEOUT"),
+                pool_max_idle_per_host: value("FLIPT_HTTP_POOL_MAX_IDLE_PER_HOST"),
+                tcp_keepalive: value("FLIPT_HTTP_TCP_KEEPALIVE"),
+            },
+        }
+    }
+
+    /// Parses every setting, recording a problem for each invalid one.
+    fn into_layer(self, source: &Path, errors: &mut Vec<String>) -> Layer {
+        let mut parser = Parser {
+            source: source.display().to_string(),
+            errors,
+        };
+
+        Layer {
+            endpoint: self
+                .endpoint
+                .and_then(|v| parser.check("endpoint", "url", v, |v| Url::parse(v).ok())),
+            auth_scheme: parser.auth_scheme(self.auth),
+            user_agent: self.user_agent,
+            namespace: self.namespace,
+            connect_timeout: parser.duration("connect_timeout", self.connect_timeout),
+            read_timeout: parser.duration("read_timeout", self.read_timeout),
+            request_timeout: parser.duration("request_timeout", self.request_timeout),
+            max_attempts: self
+                .retry
+                .max_attempts
+                .and_then(|v| parser.check("retry.max_attempts", "number", v, Value::number)),
+            base_backoff: parser.duration("retry.base_backoff", self.retry.base_backoff),
+            max_backoff: parser.duration("retry.max_backoff", self.retry.max_backoff),
+            jitter: parser.boolean("retry.jitter", self.retry.jitter),
+            ca_cert_file: self.tls.ca_cert_file,
+            client_cert_file: self.tls.client_cert_file,
+            client_key_file: self.tls.client_key_file,
+            insecure_skip_verify: parser
+                .boolean("tls.insecure_skip_verify", self.tls.insecure_skip_verify),
+            gzip: parser.boolean("http.gzip", self.http.gzip),
+            brotli: parser.boolean("http.brotli", self.http.brotli),
+            http_version: self
+                .http
+                .version
+                .and_then(|v| parser.check("http.version", "http version", v, Value::http_version)),
+            pool_idle_timeout: parser
+                .duration("http.pool_idle_timeout", self.http.pool_idle_timeout),
+            pool_max_idle_per_host: self.http.pool_max_idle_per_host.and_then(|v| {
+                parser.check("http.pool_max_idle_per_host", "number", v, |v| {
+                    v.number().map(|n| n as usize)
+                })
+            }),
+            tcp_keepalive: parser.duration("http.tcp_keepalive", self.http.tcp_keepalive),
+        }
+    }
+}
+
+/// Records a problem for every value of a source that fails to parse.
+struct Parser<'a> {
+    source: String,
+    errors: &'a mut Vec<String>,
+}
+
+impl Parser<'_> {
+    fn check<V: fmt::Display, T>(
+        &mut self,
+        name: &str,
+        kind: &str,
+        value: V,
+        parse: impl FnOnce(&V) -> Option<T>,
+    ) -> Option<T> {
+        let parsed = parse(&value);
+        if parsed.is_none() {
+            self.errors.push(format!(
+                "{}: invalid {kind} for {name}: {value}",
+                self.source
+            ));
+        }
+        parsed
+    }
+
+    fn duration(&mut self, name: &str, value: Option<Value>) -> Option<Duration> {
+        value.and_then(|v| self.check(name, "duration", v, Value::duration))
+    }
+
+    fn boolean(&mut self, name: &str, value: Option<Value>) -> Option<bool> {
+        value.and_then(|v| self.check(name, "boolean", v, Value::boolean))
+    }
+
+    fn auth_scheme(&mut self, auth: AuthSettings) -> Option<AuthScheme> {
+        match (auth.token, auth.jwt) {
+            (Some(_), Some(_)) => {
+                self.errors.push(format!(
+                    "{}: auth.token and auth.jwt cannot both be set",
+                    self.source
+                ));
+                None
+            }
+            (token, jwt) => token
+                .map(AuthScheme::BearerToken)
+                .or(jwt.map(AuthScheme::Jwt)),
+        }
+    }
+}
+
+/// Parsed settings from one source. Unset values fall through to the layer
+/// below.
+#[derive(Debug, Clone, Default)]
+struct Layer {
+    endpoint: Option<Url>,
+    auth_scheme: Option<AuthScheme>,
+    user_agent: Option<String>,
+    namespace: Option<String>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    max_attempts: Option<u32>,
+    base_backoff: Option<Duration>,
+    max_backoff: Option<Duration>,
+    jitter: Option<bool>,
+    ca_cert_file: Option<PathBuf>,
+    client_cert_file: Option<PathBuf>,
+    client_key_file: Option<PathBuf>,
+    insecure_skip_verify: Option<bool>,
+    gzip: Option<bool>,
+    brotli: Option<bool>,
+    http_version: Option<HttpVersion>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    tcp_keepalive: Option<Duration>,
+}
+
+impl Layer {
+    /// Overlays `other` on top of `self`.
+    fn merge(self, other: Layer) -> Layer {
+        Layer {
+            endpoint: other.endpoint.or(self.endpoint),
+            auth_scheme: other.auth_scheme.or(self.auth_scheme),
+            user_agent: other.user_agent.or(self.user_agent),
+            namespace: other.namespace.or(self.namespace),
+            connect_timeout: other.connect_timeout.or(self.connect_timeout),
+            read_timeout: other.read_timeout.or(self.read_timeout),
+            request_timeout: other.request_timeout.or(self.request_timeout),
+            max_attempts: other.max_attempts.or(self.max_attempts),
+            base_backoff: other.base_backoff.or(self.base_backoff),
+            max_backoff: other.max_backoff.or(self.max_backoff),
+            jitter: other.jitter.or(self.jitter),
+            ca_cert_file: other.ca_cert_file.or(self.ca_cert_file),
+            client_cert_file: other.client_cert_file.or(self.client_cert_file),
+            client_key_file: other.client_key_file.or(self.client_key_file),
+            insecure_skip_verify: other.insecure_skip_verify.or(self.insecure_skip_verify),
+            gzip: other.gzip.or(self.gzip),
+            brotli: other.brotli.or(self.brotli),
+            http_version: other.http_version.or(self.http_version),
+            pool_idle_timeout: other.pool_idle_timeout.or(self.pool_idle_timeout),
+            pool_max_idle_per_host: other.pool_max_idle_per_host.or(self.pool_max_idle_per_host),
+            tcp_keepalive: other.tcp_keepalive.or(self.tcp_keepalive),
+        }
+    }
+
+    /// Builds a config from the merged settings, recording a problem for
+    /// every invalid combination.
+    fn validate(self, errors: &mut Vec<String>) -> Config {
+        let mut config = Config::default();
+
+        if let Some(endpoint) = self.endpoint {
+            if !matches!(endpoint.scheme(), "http" | "https") {
+                errors.push(format!("endpoint {endpoint} must use http or https"));
+            }
+            config.endpoint = endpoint;
+        }
+        if let Some(auth_scheme) = self.auth_scheme {
+            config.auth_scheme = auth_scheme;
+        }
+        if let Some(user_agent) = self.user_agent {
+            config.user_agent = user_agent;
+        }
+        if let Some(namespace) = self.namespace {
+            if namespace.trim().is_empty() {
+                errors.push("namespace must not be empty".into());
+            }
+            config.namespace = namespace;
+        }
+        config.connect_timeout = self.connect_timeout;
+        config.read_timeout = self.read_timeout;
+        config.request_timeout = self.request_timeout;
+
+        let mut retry_policy = RetryPolicy::new();
+        if let Some(max_attempts) = self.max_attempts {
+            if max_attempts == 0 {
+                errors.push("retry.max_attempts must be at least 1".into());
+            }
+            retry_policy = retry_policy.set_max_attempts(max_attempts);
+        }
+        if let (Some(base), Some(max)) = (self.base_backoff, self.max_backoff) {
+            if base > max {
+                errors.push("retry.base_backoff must not exceed retry.max_backoff".into());
+            }
+        }
+        if let Some(base_backoff) = self.base_backoff {
+            retry_policy = retry_policy.set_base_backoff(base_backoff);
+        }
+        if let Some(max_backoff) = self.max_backoff {
+            retry_policy = retry_policy.set_max_backoff(max_backoff);
+        }
+        if let Some(jitter) = self.jitter {
+            retry_policy = retry_policy.set_jitter(jitter);
+        }
+        config.retry_policy = retry_policy;
+
+        let mut tls = TlsConfig::new();
+        for path in [
+            &self.ca_cert_file,
+            &self.client_cert_file,
+            &self.client_key_file,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !path.is_file() {
+                errors.push(format!("{} does not exist", path.display()));
+            }
+        }
+        if let Some(path) = self.ca_cert_file {
+            tls = tls.add_root_certificate(Pem::File(path));
+        }
+        match (self.client_cert_file, self.client_key_file) {
+            (Some(cert), Some(key)) => {
+                tls = tls.set_client_identity(Pem::File(cert), Pem::File(key));
+            }
+            (None, None) => {}
+            _ => errors
+                .push("tls.client_cert_file and tls.client_key_file must be set together".into()),
+        }
+        if let Some(insecure_skip_verify) = self.insecure_skip_verify {
+            tls = tls.set_insecure_skip_verify(insecure_skip_verify);
+        }
+        config.tls = tls;
+
+        if let Some(gzip) = self.gzip {
+            config.gzip = gzip;
+        }
+        if let Some(brotli) = self.brotli {
+            config.brotli = brotli;
+        }
+        if let Some(http_version) = self.http_version {
+            config.http_version = http_version;
+        }
+        config.pool_idle_timeout = self.pool_idle_timeout;
+        config.pool_max_idle_per_host = self.pool_max_idle_per_host;
+        config.tcp_keepalive = self.tcp_keepalive;
+        config
+    }
+}
+
+#[cfg(feature = "config_file")]
+fn read_file(path: &Path) -> std::result::Result<Settings, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => toml::from_str(&contents).map_err(|e| format!("{}: {e}", path.display())),
+        Some("yaml" | "yml") => {
+            serde_yaml::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()))
+        }
+        _ => Err(format!(
+            "{}: unsupported config file format, expected .toml, .yaml or .yml",
+            path.display()
+        )),
+    }
+}
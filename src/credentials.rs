@@ -0,0 +1,398 @@
+//! Credentials attached to every request.
+//!
+//! A [`CredentialProvider`] is asked for a [`Credential`] before each request.
+//! Credentials that carry an expiry are cached and reused until shortly before
+//! they expire, or until the server answers `401 Unauthorized`. The provider
+//! of a live client can be replaced with `set_auth_scheme`.
+//!
+//! ```
+//! use async_trait::async_trait;
+//! use chrono::{TimeDelta, Utc};
+//! use flipt::credentials::{Credential, CredentialProvider};
+//! use flipt::error::Result;
+//!
+//! struct Vault;
+//!
+//! #[async_trait]
+//! impl CredentialProvider for Vault {
+//!     async fn credential(&self) -> Result<Credential> {
+//!         // A short-lived token fetched from a secret store.
+//!         let token = "rotated-token";
+//!         Ok(Credential::bearer(token)?.set_expires_at(Utc::now() + TimeDelta::minutes(5)))
+//!     }
+//! }
+//! ```
+
+use crate::auth::kubernetes::VerifyServiceAccountRequest;
+use crate::auth::AuthClient;
+use crate::error::{Error, Result};
+use crate::middleware::{Middleware, Next};
+use crate::transport::Transport;
+use crate::AuthScheme;
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Cached credentials are refreshed this long before they expire.
+const EXPIRY_SKEW: TimeDelta = TimeDelta::seconds(30);
+
+/// A header sent with every request, optionally valid until an expiry.
+#[derive(Clone)]
+pub struct Credential {
+    name: HeaderName,
+    value: HeaderValue,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl Credential {
+    /// `Authorization: Bearer <token>`, as used by Flipt client tokens.
+    pub fn bearer(token: &str) -> Result<Self> {
+        Self::header(AUTHORIZATION.as_str(), &format!("Bearer {token}"))
+    }
+
+    /// `Authorization: JWT <token>`. Expires with the `exp` claim of the
+    /// token, when it has one.
+    pub fn jwt(token: &str) -> Result<Self> {
+        let credential = Self::header(AUTHORIZATION.as_str(), &format!("JWT {token}"))?;
+        Ok(match jwt_expiry(token) {
+            Some(expires_at) => credential.set_expires_at(expires_at),
+            None => credential,
+        })
+    }
+
+    /// Any other header, such as an API key expected by a proxy.
+    pub fn header(name: &str, value: &str) -> Result<Self> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| Error::Config(format!("invalid credential header name {name:?}")))?;
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|_| Error::Config(format!("invalid value for credential header {name}")))?;
+        value.set_sensitive(true);
+        Ok(Self {
+            name,
+            value,
+            expires_at: None,
+        })
+    }
+
+    /// Caches the credential until `v`. Credentials without an expiry are
+    /// requested again for every request.
+    pub fn set_expires_at(mut self, v: DateTime<Utc>) -> Self {
+        self.expires_at = Some(v);
+        self
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| Utc::now() + EXPIRY_SKEW < expires_at)
+    }
+}
+
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Credential")
+            .field("name", &self.name)
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads the `exp` claim of a JWT without verifying it.
+fn jwt_expiry(token: &str) -> Option<DateTime<Utc>> {
+    #[derive(Deserialize)]
+    struct Claims {
+        exp: Option<i64>,
+    }
+
+    let payload = token.split('.').nth(1)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: Claims = serde_json::from_slice(&payload).ok()?;
+    DateTime::from_timestamp(claims.exp?, 0)
+}
+
+/// Supplies the credential attached to each request.
+#[async_trait]
+pub trait CredentialProvider: Send + Sync + 'static {
+    async fn credential(&self) -> Result<Credential>;
+}
+
+impl fmt::Debug for dyn CredentialProvider {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("CredentialProvider")
+    }
+}
+
+/// A secret given inline or read from a file, such as a mounted secret, on
+/// every use.
+#[derive(Debug, Clone)]
+enum Secret {
+    Value(String),
+    File(PathBuf),
+}
+
+impl Secret {
+    async fn read(&self) -> Result<String> {
+        match self {
+            Secret::Value(v) => Ok(v.clone()),
+            Secret::File(path) => tokio::fs::read_to_string(path)
+                .await
+                .map(|v| v.trim().to_string())
+                .map_err(|e| Error::Config(format!("failed to read {}: {e}", path.display()))),
+        }
+    }
+}
+
+/// Sends a Flipt client token as `Authorization: Bearer <token>`.
+#[derive(Debug, Clone)]
+pub struct Bearer(Secret);
+
+impl Bearer {
+    pub fn new(token: &str) -> Self {
+        Self(Secret::Value(token.into()))
+    }
+
+    /// Reads the token from `path` for every request, so it can be rotated
+    /// without restarting.
+    pub fn from_file(path: impl Into<PathBuf>) -> Self {
+        Self(Secret::File(path.into()))
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for Bearer {
+    async fn credential(&self) -> Result<Credential> {
+        Credential::bearer(&self.0.read().await?)
+    }
+}
+
+/// Sends a JWT as `Authorization: JWT <token>`, cached until its `exp` claim.
+#[derive(Debug, Clone)]
+pub struct Jwt(Secret);
+
+impl Jwt {
+    pub fn new(token: &str) -> Self {
+        Self(Secret::Value(token.into()))
+    }
+
+    /// Reads the token from `path` whenever the previous one has expired.
+    pub fn from_file(path: impl Into<PathBuf>) -> Self {
+        Self(Secret::File(path.into()))
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for Jwt {
+    async fn credential(&self) -> Result<Credential> {
+        Credential::jwt(&self.0.read().await?)
+    }
+}
+
+/// Sends a fixed header with every request.
+#[derive(Debug, Clone)]
+pub struct StaticHeader {
+    name: String,
+    value: String,
+}
+
+impl StaticHeader {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for StaticHeader {
+    async fn credential(&self) -> Result<Credential> {
+        Credential::header(&self.name, &self.value)
+    }
+}
+
+/// Exchanges a Kubernetes service account token for a client token. The
+/// token file is read for every exchange, so tokens rotated by the kubelet are
+/// picked up.
+#[derive(Debug)]
+struct ServiceAccount {
+    token: Secret,
+    client: AuthClient,
+}
+
+#[async_trait]
+impl CredentialProvider for ServiceAccount {
+    async fn credential(&self) -> Result<Credential> {
+        let verification = self
+            .client
+            .kubernetes()
+            .verify_service_account(&VerifyServiceAccountRequest {
+                service_account_token: self.token.read().await?,
+            })
+            .await?;
+        // A client token without an expiry stays valid until it is revoked,
+        // so it is kept until the server answers 401.
+        let expires_at = verification
+            .authentication
+            .expires_at
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        Ok(Credential::bearer(&verification.client_token)?.set_expires_at(expires_at))
+    }
+}
+
+impl AuthScheme {
+    /// `exchange` sends the requests of schemes that trade a credential for
+    /// a client token.
+    fn into_provider(self, exchange: &Transport) -> Option<Arc<dyn CredentialProvider>> {
+        match self {
+            AuthScheme::None => None,
+            AuthScheme::BearerToken(token) => Some(Arc::new(Bearer::new(&token))),
+            AuthScheme::Jwt(token) => Some(Arc::new(Jwt::new(&token))),
+            AuthScheme::Kubernetes(path) => Some(Arc::new(ServiceAccount {
+                token: Secret::File(path),
+                client: AuthClient::from_transport(exchange.clone()),
+            })),
+            AuthScheme::Provider(provider) => Some(provider),
+        }
+    }
+}
+
+/// The built-in middleware attaching the current credential to requests.
+#[derive(Debug)]
+pub(crate) struct Authenticator {
+    state: Mutex<State>,
+    /// Held while fetching from the provider, so concurrent requests wait
+    /// for one fetch instead of each starting their own.
+    fetch: tokio::sync::Mutex<()>,
+    exchange: Transport,
+}
+
+#[derive(Debug)]
+struct State {
+    provider: Option<Arc<dyn CredentialProvider>>,
+    /// Incremented whenever the provider is replaced, so that credentials
+    /// fetched from an earlier provider are not cached.
+    generation: u64,
+    cached: Option<Credential>,
+    /// Whether the last credential of the provider had an expiry. Fetches
+    /// of credentials that are never cached are not shared, so requests do
+    /// not queue behind each other for them.
+    expiring: bool,
+}
+
+enum Lookup {
+    Ready(Option<Credential>),
+    Fetch {
+        provider: Arc<dyn CredentialProvider>,
+        generation: u64,
+        shared: bool,
+    },
+}
+
+impl Authenticator {
+    /// `exchange` must not itself be authenticated by this authenticator.
+    pub(crate) fn new(auth_scheme: AuthScheme, exchange: Transport) -> Self {
+        Self {
+            state: Mutex::new(State {
+                provider: auth_scheme.into_provider(&exchange),
+                generation: 0,
+                cached: None,
+                expiring: true,
+            }),
+            fetch: tokio::sync::Mutex::new(()),
+            exchange,
+        }
+    }
+
+    /// Replaces the provider and drops any cached credential.
+    pub(crate) fn set_auth_scheme(&self, auth_scheme: AuthScheme) {
+        let provider = auth_scheme.into_provider(&self.exchange);
+        let mut state = self.state.lock().unwrap();
+        state.provider = provider;
+        state.generation += 1;
+        state.cached = None;
+        state.expiring = true;
+    }
+
+    fn lookup(&self) -> Lookup {
+        let state = self.state.lock().unwrap();
+        match (&state.provider, &state.cached) {
+            (None, _) => Lookup::Ready(None),
+            (Some(_), Some(cached)) if cached.is_fresh() => Lookup::Ready(Some(cached.clone())),
+            (Some(provider), _) => Lookup::Fetch {
+                provider: provider.clone(),
+                generation: state.generation,
+                shared: state.expiring,
+            },
+        }
+    }
+
+    async fn credential(&self) -> Result<Option<Credential>> {
+        let _fetch = match self.lookup() {
+            Lookup::Ready(credential) => return Ok(credential),
+            Lookup::Fetch {
+                provider,
+                generation,
+                shared: false,
+            } => return self.fetch_from(provider, generation).await.map(Some),
+            Lookup::Fetch { shared: true, .. } => self.fetch.lock().await,
+        };
+
+        match self.lookup() {
+            Lookup::Ready(credential) => Ok(credential),
+            Lookup::Fetch {
+                provider,
+                generation,
+                ..
+            } => self.fetch_from(provider, generation).await.map(Some),
+        }
+    }
+
+    /// Caches the fetched credential when it expires and `provider` has not
+    /// been replaced in the meantime.
+    async fn fetch_from(
+        &self,
+        provider: Arc<dyn CredentialProvider>,
+        generation: u64,
+    ) -> Result<Credential> {
+        let credential = provider.credential().await?;
+        let mut state = self.state.lock().unwrap();
+        if state.generation == generation {
+            state.expiring = credential.expires_at.is_some();
+            if state.expiring {
+                state.cached = Some(credential.clone());
+            }
+        }
+        Ok(credential)
+    }
+}
+
+#[async_trait]
+impl Middleware for Authenticator {
+    async fn handle(
+        &self,
+        mut request: reqwest::Request,
+        next: Next<'_>,
+    ) -> Result<reqwest::Response> {
+        if let Some(credential) = self.credential().await? {
+            request
+                .headers_mut()
+                .insert(credential.name, credential.value);
+        }
+
+        let response = next.run(request).await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.state.lock().unwrap().cached.take();
+        }
+        Ok(response)
+    }
+}
@@ -1,20 +1,140 @@
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
 use serde::Deserialize;
 use std::fmt;
 
+pub type Result<T> = std::result::Result<T, Error>;
+
+const BAD_REQUEST_TYPE: &str = "type.googleapis.com/google.rpc.BadRequest";
+const MAX_DISPLAYED_BODY: usize = 256;
+
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
-    Upstream(UpstreamError),
+    NotFound(Box<ErrorResponse>),
+    Unauthenticated(Box<ErrorResponse>),
+    PermissionDenied(Box<ErrorResponse>),
+    InvalidArgument(Box<ErrorResponse>),
+    AlreadyExists(Box<ErrorResponse>),
+    Unavailable(Box<ErrorResponse>),
+    /// The request deadline was exceeded. The response is absent when the
+    /// client gave up before the server answered.
+    Timeout(Option<Box<ErrorResponse>>),
+    /// A response body could not be decoded.
+    Decode(Box<DecodeError>),
+    /// Any other non-success response.
+    Upstream(Box<ErrorResponse>),
     Request(reqwest::Error),
+    Config(String),
+    /// The resource changed concurrently, such as rules reordered by someone
+    /// else while the client was reordering them.
+    Conflict(String),
+    /// A browser login was denied by the identity provider or could not be
+    /// completed.
+    Login(String),
     Internal(String),
 }
 
-impl std::error::Error for Error {}
+impl Error {
+    /// Classifies an error response by its gRPC code, falling back to the
+    /// HTTP status when the body is not a Flipt error or the code is not one
+    /// of the typed variants.
+    pub(crate) fn from_response(resp: ErrorResponse) -> Self {
+        let resp = Box::new(resp);
+        match resp.error.as_ref().map(|e| e.code) {
+            Some(3) => return Error::InvalidArgument(resp),
+            Some(4) => return Error::Timeout(Some(resp)),
+            Some(5) => return Error::NotFound(resp),
+            Some(6) => return Error::AlreadyExists(resp),
+            Some(7) => return Error::PermissionDenied(resp),
+            Some(14) => return Error::Unavailable(resp),
+            Some(16) => return Error::Unauthenticated(resp),
+            _ => {}
+        }
+
+        match resp.status {
+            StatusCode::BAD_REQUEST => Error::InvalidArgument(resp),
+            StatusCode::UNAUTHORIZED => Error::Unauthenticated(resp),
+            StatusCode::FORBIDDEN => Error::PermissionDenied(resp),
+            StatusCode::NOT_FOUND => Error::NotFound(resp),
+            StatusCode::CONFLICT => Error::AlreadyExists(resp),
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE => Error::Unavailable(resp),
+            StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => Error::Timeout(Some(resp)),
+            _ => Error::Upstream(resp),
+        }
+    }
+
+    /// A [`Error::NotFound`] raised by the client itself, shaped like the
+    /// one Flipt returns.
+    pub(crate) fn not_found(message: String) -> Self {
+        Error::NotFound(Box::new(ErrorResponse::local(
+            StatusCode::NOT_FOUND,
+            5,
+            message,
+        )))
+    }
+
+    /// A [`Error::InvalidArgument`] raised by the client itself, shaped like
+    /// the one Flipt returns.
+    pub(crate) fn invalid_argument(message: String) -> Self {
+        Error::InvalidArgument(Box::new(ErrorResponse::local(
+            StatusCode::BAD_REQUEST,
+            3,
+            message,
+        )))
+    }
+
+    /// The error response returned by the server, if any.
+    pub fn response(&self) -> Option<&ErrorResponse> {
+        match self {
+            Error::NotFound(r)
+            | Error::Unauthenticated(r)
+            | Error::PermissionDenied(r)
+            | Error::InvalidArgument(r)
+            | Error::AlreadyExists(r)
+            | Error::Unavailable(r)
+            | Error::Upstream(r) => Some(r),
+            Error::Timeout(r) => r.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The HTTP status of the response that caused this error, if any.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Decode(e) => Some(e.status),
+            _ => self.response().map(|r| r.status),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(e) => Some(&e.source),
+            Error::Request(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::Upstream(e) => write!(f, "{e}"),
+            Error::NotFound(e)
+            | Error::Unauthenticated(e)
+            | Error::PermissionDenied(e)
+            | Error::InvalidArgument(e)
+            | Error::AlreadyExists(e)
+            | Error::Unavailable(e)
+            | Error::Upstream(e) => write!(f, "{e}"),
+            Error::Timeout(Some(e)) => write!(f, "{e}"),
+            Error::Timeout(None) => write!(f, "request timed out"),
+            Error::Decode(e) => write!(f, "{e}"),
             Error::Request(e) => write!(f, "{e}"),
+            Error::Config(e) => write!(f, "invalid configuration: {e}"),
+            Error::Conflict(e) => write!(f, "conflict: {e}"),
+            Error::Login(e) => write!(f, "login failed: {e}"),
             Error::Internal(e) => write!(f, "{e}"),
         }
     }
@@ -22,7 +142,11 @@ impl fmt::Display for Error {
 
 impl From<reqwest::Error> for Error {
     fn from(v: reqwest::Error) -> Self {
-        Error::Request(v)
+        if v.is_timeout() {
+            Error::Timeout(None)
+        } else {
+            Error::Request(v)
+        }
     }
 }
 
@@ -32,6 +156,73 @@ impl From<url::ParseError> for Error {
     }
 }
 
+/// A non-success response, kept whole for debugging.
+///
+/// `error` is absent when the body is not a Flipt error envelope, for
+/// example an HTML error page returned by a proxy in front of Flipt.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ErrorResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+    pub error: Option<UpstreamError>,
+}
+
+impl ErrorResponse {
+    /// A response for an error detected before sending the request.
+    fn local(status: StatusCode, code: i32, message: String) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: String::new(),
+            error: Some(UpstreamError {
+                code,
+                message,
+                details: None,
+            }),
+        }
+    }
+
+    /// The Flipt error message, or the raw body when there is none.
+    pub fn message(&self) -> &str {
+        match &self.error {
+            Some(e) => &e.message,
+            None => &self.body,
+        }
+    }
+
+    /// Field violations carried in `google.rpc.BadRequest` details.
+    pub fn field_violations(&self) -> Vec<FieldViolation> {
+        self.error
+            .iter()
+            .flat_map(|e| e.details.iter().flatten())
+            .filter(|d| d.get("@type").and_then(|t| t.as_str()) == Some(BAD_REQUEST_TYPE))
+            .filter_map(|d| serde_json::from_value::<BadRequest>(d.clone()).ok())
+            .flat_map(|d| d.field_violations)
+            .collect()
+    }
+}
+
+impl fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(error) = &self.error {
+            return write!(f, "{error}");
+        }
+
+        write!(f, "unexpected response {}", self.status)?;
+        let body = self.body.trim();
+        if !body.is_empty() {
+            let end = body
+                .char_indices()
+                .nth(MAX_DISPLAYED_BODY)
+                .map_or(body.len(), |(i, _)| i);
+            write!(f, ": {}", &body[..end])?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[non_exhaustive]
 pub struct UpstreamError {
@@ -54,3 +245,44 @@ impl fmt::Display for UpstreamError {
         Ok(())
     }
 }
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct FieldViolation {
+    pub field: String,
+    pub description: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BadRequest {
+    #[serde(default)]
+    field_violations: Vec<FieldViolation>,
+}
+
+/// A response body that did not match the expected shape.
+#[derive(Debug)]
+pub struct DecodeError {
+    pub status: StatusCode,
+    pub body: String,
+    pub source: serde_json::Error,
+}
+
+impl DecodeError {
+    pub(crate) fn new(status: StatusCode, body: &[u8], source: serde_json::Error) -> Self {
+        Self {
+            status,
+            body: String::from_utf8_lossy(body).into_owned(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "failed to decode response with status {}: {}",
+            self.status, self.source
+        )
+    }
+}
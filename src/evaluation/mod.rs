@@ -1,39 +1,129 @@
 use crate::api::{ApiClient, Result};
+use crate::telemetry;
+use crate::transport::{CallOptions, Route};
+use crate::wire::wire_enum;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::time::Duration;
 
-pub struct EvaluationClient<'client> {
-    client: &'client ApiClient,
+#[derive(Debug, Clone)]
+pub struct EvaluationClient {
+    client: ApiClient,
+    options: CallOptions,
 }
 
-impl<'client> EvaluationClient<'client> {
-    pub fn new(client: &'client ApiClient) -> Self {
-        Self { client }
+impl EvaluationClient {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn in_namespace(mut self, namespace_key: &str) -> Self {
+        self.options.namespace_key = Some(namespace_key.into());
+        self
     }
 
     pub async fn boolean(&self, eval: &EvaluateRequest) -> Result<BooleanEvaluation> {
-        let path = "/evaluate/v1/boolean".to_string();
+        let eval = self.with_namespace(eval);
+        let route = Route::new("/evaluate/v1/boolean")
+            .namespace(&eval.namespace_key)
+            .flag(&eval.flag_key);
 
-        self.client.post(&path, Some(eval)).await
+        let evaluation: BooleanEvaluation = self
+            .client
+            .transport
+            .post_idempotent(route, Some(&*eval), &self.options)
+            .await?;
+        telemetry::boolean_evaluated(&eval.namespace_key, &evaluation);
+        Ok(evaluation)
     }
 
     pub async fn variant(&self, eval: &EvaluateRequest) -> Result<VariantEvaluation> {
-        let path = "/evaluate/v1/variant".to_string();
+        let eval = self.with_namespace(eval);
+        let route = Route::new("/evaluate/v1/variant")
+            .namespace(&eval.namespace_key)
+            .flag(&eval.flag_key);
 
-        self.client.post(&path, Some(eval)).await
+        let evaluation: VariantEvaluation = self
+            .client
+            .transport
+            .post_idempotent(route, Some(&*eval), &self.options)
+            .await?;
+        telemetry::variant_evaluated(&eval.namespace_key, &evaluation);
+        Ok(evaluation)
     }
 
     pub async fn batch(&self, batch: &BatchEvaluateRequest) -> Result<BatchEvaluation> {
-        let path = "/evaluate/v1/batch".to_string();
+        let batch = if batch.requests.iter().any(|r| r.namespace_key.is_empty()) {
+            Cow::Owned(BatchEvaluateRequest {
+                requests: batch
+                    .requests
+                    .iter()
+                    .map(|r| self.with_namespace(r).into_owned())
+                    .collect(),
+                request_id: batch.request_id.clone(),
+                reference: batch.reference.clone(),
+            })
+        } else {
+            Cow::Borrowed(batch)
+        };
+        let route = Route::new("/evaluate/v1/batch");
 
-        self.client.post(&path, Some(batch)).await
+        let evaluation: BatchEvaluation = self
+            .client
+            .transport
+            .post_idempotent(route, Some(&*batch), &self.options)
+            .await?;
+        for (request, response) in batch.requests.iter().zip(&evaluation.responses) {
+            match response {
+                EvaluationResult::Boolean(boolean) => {
+                    telemetry::boolean_evaluated(&request.namespace_key, boolean)
+                }
+                EvaluationResult::Variant(variant) => {
+                    telemetry::variant_evaluated(&request.namespace_key, variant)
+                }
+                EvaluationResult::Error(error) => telemetry::evaluation_failed(error),
+                EvaluationResult::Unknown(_) => {}
+            }
+        }
+        Ok(evaluation)
+    }
+
+    /// Fills in the scoped or configured namespace when `eval` has none.
+    fn with_namespace<'a>(&self, eval: &'a EvaluateRequest) -> Cow<'a, EvaluateRequest> {
+        if !eval.namespace_key.is_empty() {
+            return Cow::Borrowed(eval);
+        }
+        let namespace_key = self.client.transport.namespace_or(&None, &self.options);
+        Cow::Owned(EvaluateRequest {
+            namespace_key: namespace_key.into(),
+            ..eval.clone()
+        })
     }
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct BatchEvaluateRequest {
     pub requests: Vec<EvaluateRequest>,
+    /// Correlates the evaluations with the caller's logs. Flipt generates
+    /// one when unset.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub request_id: String,
+    /// The snapshot or version of the flag state to evaluate against, for
+    /// storage backends that keep several.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub reference: String,
 }
 
 #[derive(Debug, Default, Clone, Serialize)]
@@ -41,8 +131,17 @@ pub struct BatchEvaluateRequest {
 pub struct EvaluateRequest {
     pub context: HashMap<String, String>,
     pub entity_id: String,
+    /// Left empty, the namespace of a scoped client or the configured one.
     pub namespace_key: String,
     pub flag_key: String,
+    /// Correlates the evaluation with the caller's logs. Flipt generates one
+    /// when unset.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub request_id: String,
+    /// The snapshot or version of the flag state to evaluate against, for
+    /// storage backends that keep several.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub reference: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -71,7 +170,15 @@ pub struct VariantEvaluation {
     pub flag_key: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl VariantEvaluation {
+    /// Whether no rule matched and the default variant of the flag was
+    /// served.
+    pub fn is_default_variant(&self) -> bool {
+        self.reason == Reason::Default && !self.variant_key.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ErrorEvaluation {
     pub flag_key: String,
@@ -83,45 +190,75 @@ pub struct ErrorEvaluation {
 #[serde(rename_all = "camelCase")]
 pub struct BatchEvaluation {
     pub request_id: String,
-    pub responses: Vec<Response>,
+    pub responses: Vec<EvaluationResult>,
     pub request_duration_millis: f64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// The outcome of one evaluation of a batch, in the order of the requests.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "Response")]
+pub enum EvaluationResult {
+    Boolean(BooleanEvaluation),
+    Variant(VariantEvaluation),
+    Error(ErrorEvaluation),
+    /// A response type this client does not know.
+    Unknown(String),
+}
+
+/// A batch response as sent on the wire, with the evaluation for its type
+/// set.
+#[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Response {
-    pub r#type: ResponseType,
-    pub boolean_response: Option<BooleanEvaluation>,
-    pub variant_response: Option<VariantEvaluation>,
-    pub error_response: Option<ErrorEvaluation>,
+struct Response {
+    r#type: ResponseType,
+    boolean_response: Option<BooleanEvaluation>,
+    variant_response: Option<VariantEvaluation>,
+    error_response: Option<ErrorEvaluation>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub enum ResponseType {
-    #[serde(rename = "VARIANT_EVALUATION_RESPONSE_TYPE")]
-    Variant,
-    #[serde(rename = "BOOLEAN_EVALUATION_RESPONSE_TYPE")]
-    Boolean,
-    #[serde(rename = "ERROR_EVALUATION_RESPONSE_TYPE")]
-    Error,
+impl TryFrom<Response> for EvaluationResult {
+    type Error = String;
+
+    fn try_from(v: Response) -> std::result::Result<Self, String> {
+        let missing = |field: &str| format!("{} response without {field}", v.r#type.as_str());
+        match &v.r#type {
+            ResponseType::Boolean => v
+                .boolean_response
+                .map(EvaluationResult::Boolean)
+                .ok_or_else(|| missing("booleanResponse")),
+            ResponseType::Variant => v
+                .variant_response
+                .map(EvaluationResult::Variant)
+                .ok_or_else(|| missing("variantResponse")),
+            ResponseType::Error => v
+                .error_response
+                .map(EvaluationResult::Error)
+                .ok_or_else(|| missing("errorResponse")),
+            ResponseType::Unknown(t) => Ok(EvaluationResult::Unknown(t.clone())),
+        }
+    }
+}
+
+wire_enum! {
+    pub enum ResponseType {
+        Variant = "VARIANT_EVALUATION_RESPONSE_TYPE",
+        Boolean = "BOOLEAN_EVALUATION_RESPONSE_TYPE",
+        Error = "ERROR_EVALUATION_RESPONSE_TYPE",
+    }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
-pub enum ErrorEvaluationReason {
-    #[serde(rename = "UNKNOWN_ERROR_EVALUATION_REASON")]
-    Unknown,
-    #[serde(rename = "NOT_FOUND_ERROR_EVALUATION_REASON")]
-    NotFound,
+wire_enum! {
+    pub enum ErrorEvaluationReason {
+        Unspecified = "UNKNOWN_ERROR_EVALUATION_REASON",
+        NotFound = "NOT_FOUND_ERROR_EVALUATION_REASON",
+    }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
-pub enum Reason {
-    #[serde(rename = "UNKNOWN_EVALUATION_REASON")]
-    Unknown,
-    #[serde(rename = "FLAG_DISABLED_EVALUATION_REASON")]
-    FlagDisabled,
-    #[serde(rename = "MATCH_EVALUATION_REASON")]
-    Match,
-    #[serde(rename = "DEFAULT_EVALUATION_REASON")]
-    Default,
+wire_enum! {
+    pub enum Reason {
+        Unspecified = "UNKNOWN_EVALUATION_REASON",
+        FlagDisabled = "FLAG_DISABLED_EVALUATION_REASON",
+        Match = "MATCH_EVALUATION_REASON",
+        Default = "DEFAULT_EVALUATION_REASON",
+    }
 }
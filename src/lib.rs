@@ -1,30 +1,71 @@
 pub mod api;
 pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod client;
+pub mod config;
+pub mod credentials;
 pub mod error;
 pub mod evaluation;
 pub mod meta;
+pub mod middleware;
+pub mod pagination;
+pub mod retry;
+mod telemetry;
+pub mod tls;
+mod transport;
+mod wire;
 
-use anyhow::Result;
-use std::env;
+pub use client::FliptClient;
+pub use config::ConfigBuilder;
+
+use auth::kubernetes::DEFAULT_SERVICE_ACCOUNT_TOKEN_PATH;
+use credentials::CredentialProvider;
+use error::Result;
+use middleware::Middleware;
+use retry::RetryPolicy;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tls::TlsConfig;
 use url::Url;
 
 const VERSION: Option<&str> = option_env!("CARGO_PKG_VERSION");
 const DEFAULT_USER_AGENT: &str = "flipt-rust";
+const DEFAULT_NAMESPACE: &str = "default";
 
 #[derive(Debug, Clone)]
 pub struct Config {
     endpoint: Url,
     auth_scheme: AuthScheme,
     user_agent: String,
+    retry_policy: RetryPolicy,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    tls: TlsConfig,
+    namespace: String,
+    gzip: bool,
+    brotli: bool,
+    http_version: HttpVersion,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    tcp_keepalive: Option<Duration>,
 }
 
 impl Config {
+    /// Starts a layered configuration from a config file, the environment
+    /// and code overrides. See [`config`] for the priority order.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+
+    /// Builds a config from the `FLIPT_*` environment variables, including
+    /// `FLIPT_CONFIG_FILE`. Like `Config::builder().build()`, except that the
+    /// endpoint has no default and must be set.
     pub fn new_from_env() -> Result<Self> {
-        Ok(Self {
-            endpoint: endpoint_from_env()?,
-            auth_scheme: auth_scheme_from_env(),
-            user_agent: user_agent_from_env(),
-        })
+        Self::builder().require_endpoint().build()
     }
 
     pub fn new(endpoint: Url, auth_scheme: AuthScheme) -> Self {
@@ -32,6 +73,19 @@ impl Config {
             endpoint,
             auth_scheme,
             user_agent: format!("{}/{}", DEFAULT_USER_AGENT, VERSION.unwrap_or("unknown")),
+            retry_policy: RetryPolicy::default(),
+            connect_timeout: None,
+            read_timeout: None,
+            request_timeout: None,
+            middlewares: Vec::new(),
+            tls: TlsConfig::default(),
+            namespace: DEFAULT_NAMESPACE.into(),
+            gzip: true,
+            brotli: true,
+            http_version: HttpVersion::default(),
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
+            tcp_keepalive: None,
         }
     }
 
@@ -39,6 +93,88 @@ impl Config {
         self.user_agent = v.into();
         self
     }
+
+    pub fn set_retry_policy(mut self, v: RetryPolicy) -> Self {
+        self.retry_policy = v;
+        self
+    }
+
+    /// Namespace used by requests that do not set one. Defaults to
+    /// `"default"`.
+    pub fn set_namespace(mut self, v: &str) -> Self {
+        self.namespace = v.into();
+        self
+    }
+
+    /// Maximum time to wait for a connection to be established.
+    pub fn set_connect_timeout(mut self, v: Duration) -> Self {
+        self.connect_timeout = Some(v);
+        self
+    }
+
+    /// Maximum time to wait between two reads of a response.
+    pub fn set_read_timeout(mut self, v: Duration) -> Self {
+        self.read_timeout = Some(v);
+        self
+    }
+
+    /// Maximum total time for a request, from connecting until the response
+    /// body has been read, retries included. Sub-clients can override it with
+    /// `with_timeout`.
+    pub fn set_request_timeout(mut self, v: Duration) -> Self {
+        self.request_timeout = Some(v);
+        self
+    }
+
+    /// Appends a middleware to the chain every request goes through.
+    /// Middlewares run in the order they are added.
+    pub fn add_middleware<M: Middleware>(mut self, v: M) -> Self {
+        self.middlewares.push(Arc::new(v));
+        self
+    }
+
+    pub fn set_tls(mut self, v: TlsConfig) -> Self {
+        self.tls = v;
+        self
+    }
+
+    /// Whether gzip compressed responses are accepted. Enabled by default.
+    pub fn set_gzip(mut self, v: bool) -> Self {
+        self.gzip = v;
+        self
+    }
+
+    /// Whether brotli compressed responses are accepted. Enabled by default.
+    pub fn set_brotli(mut self, v: bool) -> Self {
+        self.brotli = v;
+        self
+    }
+
+    pub fn set_http_version(mut self, v: HttpVersion) -> Self {
+        self.http_version = v;
+        self
+    }
+
+    /// How long an idle connection is kept in the pool. Defaults to 90
+    /// seconds.
+    pub fn set_pool_idle_timeout(mut self, v: Duration) -> Self {
+        self.pool_idle_timeout = Some(v);
+        self
+    }
+
+    /// Maximum number of idle connections kept in the pool for each host.
+    /// Unlimited by default.
+    pub fn set_pool_max_idle_per_host(mut self, v: usize) -> Self {
+        self.pool_max_idle_per_host = Some(v);
+        self
+    }
+
+    /// Interval of TCP keepalive probes on idle connections. Disabled by
+    /// default.
+    pub fn set_tcp_keepalive(mut self, v: Duration) -> Self {
+        self.tcp_keepalive = Some(v);
+        self
+    }
 }
 
 impl Default for Config {
@@ -50,33 +186,60 @@ impl Default for Config {
     }
 }
 
-fn endpoint_from_env() -> Result<Url, url::ParseError> {
-    let endpoint = env::var("FLIPT_ENDPOINT").unwrap_or_default();
-    Url::parse(&endpoint)
-}
+/// Parses durations such as `500ms`, `5s`, `1m` or `1h`. A bare number is a
+/// number of seconds.
+fn parse_duration(v: &str) -> Option<Duration> {
+    let v = v.trim();
+    let split = v.find(|c: char| !c.is_ascii_digit()).unwrap_or(v.len());
+    let (amount, unit) = v.split_at(split);
+    let amount: u64 = amount.parse().ok()?;
 
-fn user_agent_from_env() -> String {
-    env::var("FLIPT_USER_AGENT")
-        .unwrap_or_else(|_| format!("{}/{}", DEFAULT_USER_AGENT, VERSION.unwrap_or("unknown")))
+    match unit {
+        "ms" => Some(Duration::from_millis(amount)),
+        "" | "s" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_secs(amount * 60)),
+        "h" => Some(Duration::from_secs(amount * 60 * 60)),
+        _ => None,
+    }
 }
 
-fn auth_scheme_from_env() -> AuthScheme {
-    let token = env::var("FLIPT_AUTH_TOKEN").unwrap_or_default();
-    if token.is_empty() {
-        AuthScheme::None
-    } else {
-        AuthScheme::BearerToken(token)
-    }
+/// The HTTP version used to talk to Flipt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HttpVersion {
+    /// HTTP/2 when the server offers it during the TLS handshake, HTTP/1.1
+    /// otherwise.
+    #[default]
+    Auto,
+    Http1Only,
+    /// HTTP/2 without negotiation, also over plain HTTP, such as to an h2c
+    /// sidecar.
+    Http2PriorKnowledge,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum AuthScheme {
+    #[default]
     None,
     BearerToken(String),
+    /// A JWT, sent as `Authorization: JWT <token>`.
+    Jwt(String),
+    /// Exchanges the Kubernetes service account token read from this path
+    /// for a client token, and again whenever that client token is about to
+    /// expire or is rejected. See [`AuthScheme::kubernetes`].
+    Kubernetes(PathBuf),
+    /// Credentials supplied by a provider for every request, such as rotating
+    /// tokens.
+    Provider(Arc<dyn CredentialProvider>),
 }
 
-impl Default for AuthScheme {
-    fn default() -> Self {
-        Self::None
+impl AuthScheme {
+    /// Kubernetes authentication with the token the kubelet mounts into
+    /// every pod.
+    pub fn kubernetes() -> Self {
+        AuthScheme::Kubernetes(DEFAULT_SERVICE_ACCOUNT_TOKEN_PATH.into())
+    }
+
+    pub fn provider<P: CredentialProvider>(v: P) -> Self {
+        AuthScheme::Provider(Arc::new(v))
     }
 }
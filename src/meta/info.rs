@@ -1,16 +1,33 @@
 use crate::meta::{MetaClient, Result};
+use crate::transport::{CallOptions, Route};
+use std::time::Duration;
 
-pub struct InfoClient<'client> {
-    client: &'client MetaClient,
+#[derive(Debug, Clone)]
+pub struct InfoClient {
+    client: MetaClient,
+    options: CallOptions,
 }
 
-impl<'client> InfoClient<'client> {
-    pub fn new(client: &'client MetaClient) -> Self {
-        Self { client }
+impl InfoClient {
+    pub fn new(client: &MetaClient) -> Self {
+        Self {
+            client: client.clone(),
+            options: CallOptions::default(),
+        }
+    }
+
+    /// Overrides the configured request timeout for calls made through this client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
     }
 
     pub async fn get(&self) -> Result<String> {
-        let result: Result<serde_json::Value> = self.client.get("/meta/info", None::<&()>).await;
+        let result: Result<serde_json::Value> = self
+            .client
+            .transport
+            .get(Route::new("/meta/info"), None::<&()>, &self.options)
+            .await;
 
         match result {
             Ok(value) => Ok(value.to_string()),
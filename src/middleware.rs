@@ -0,0 +1,68 @@
+use crate::error::Result;
+use async_trait::async_trait;
+use std::fmt;
+use std::sync::Arc;
+
+/// Intercepts every request sent by a client.
+///
+/// A middleware can change the outgoing request, short-circuit it by
+/// returning early, or inspect and change the response returned by
+/// `next.run`. Middlewares run in the order they were added to the
+/// [`Config`](crate::Config), after the built-in authentication middleware.
+///
+/// ```
+/// use async_trait::async_trait;
+/// use flipt::error::Result;
+/// use flipt::middleware::{Middleware, Next};
+///
+/// struct Tenant(&'static str);
+///
+/// #[async_trait]
+/// impl Middleware for Tenant {
+///     async fn handle(
+///         &self,
+///         mut request: reqwest::Request,
+///         next: Next<'_>,
+///     ) -> Result<reqwest::Response> {
+///         request
+///             .headers_mut()
+///             .insert("X-Tenant", self.0.parse().unwrap());
+///         next.run(request).await
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Middleware: Send + Sync + 'static {
+    async fn handle(&self, request: reqwest::Request, next: Next<'_>) -> Result<reqwest::Response>;
+}
+
+impl fmt::Debug for dyn Middleware {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Middleware")
+    }
+}
+
+/// The remainder of the middleware chain.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a reqwest::Client,
+    middlewares: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(client: &'a reqwest::Client, middlewares: &'a [Arc<dyn Middleware>]) -> Self {
+        Self {
+            client,
+            middlewares,
+        }
+    }
+
+    /// Passes the request to the next middleware, or sends it when this is
+    /// the end of the chain.
+    pub async fn run(self, request: reqwest::Request) -> Result<reqwest::Response> {
+        match self.middlewares.split_first() {
+            Some((current, rest)) => current.handle(request, Next::new(self.client, rest)).await,
+            None => Ok(self.client.execute(request).await?),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Streams over paginated list endpoints.
+//!
+//! The `stream` methods on list clients return a [`PageStream`] that requests
+//! pages lazily, as items are consumed, and ends after the page whose
+//! `next_page_token` is empty.
+
+use crate::error::{Error, Result};
+use futures_core::Stream;
+use futures_util::future::BoxFuture;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A page of items and the token of the page after it.
+pub(crate) struct Page<T> {
+    pub(crate) items: Vec<T>,
+    pub(crate) next_page_token: String,
+}
+
+type Fetch<T> = Box<dyn FnMut(String, usize) -> BoxFuture<'static, Result<Page<T>>> + Send>;
+
+/// A [`Stream`] of the items of a list endpoint, across all its pages.
+///
+/// Nothing is requested until the stream is first polled. By default the
+/// next page is only requested once the current one has been consumed; with
+/// [`PageStream::prefetch`] up to that many pages are requested ahead while
+/// the current page is being consumed. An error ends the stream after the
+/// items received before it.
+pub struct PageStream<T> {
+    fetch: Fetch<T>,
+    page_size: usize,
+    prefetch: usize,
+    next_page_token: Option<String>,
+    in_flight: Option<BoxFuture<'static, Result<Page<T>>>>,
+    pages: VecDeque<VecDeque<T>>,
+    error: Option<Error>,
+}
+
+impl<T> PageStream<T> {
+    pub(crate) fn new<F>(page_token: String, page_size: usize, fetch: F) -> Self
+    where
+        F: FnMut(String, usize) -> BoxFuture<'static, Result<Page<T>>> + Send + 'static,
+    {
+        Self {
+            fetch: Box::new(fetch),
+            page_size,
+            prefetch: 0,
+            next_page_token: Some(page_token),
+            in_flight: None,
+            pages: VecDeque::new(),
+            error: None,
+        }
+    }
+
+    /// Number of items requested per page. Defaults to the `limit` of the
+    /// list request.
+    pub fn page_size(mut self, v: usize) -> Self {
+        self.page_size = v;
+        self
+    }
+
+    /// Number of pages requested ahead of the one being consumed. Defaults
+    /// to 0.
+    pub fn prefetch(mut self, v: usize) -> Self {
+        self.prefetch = v;
+        self
+    }
+
+    fn buffered(&self) -> usize {
+        self.pages.iter().filter(|p| !p.is_empty()).count()
+    }
+}
+
+// Items are only moved in and out of the buffer, never pinned.
+impl<T> Unpin for PageStream<T> {}
+
+impl<T> Stream for PageStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if this.in_flight.is_none() && this.buffered() <= this.prefetch {
+                if let Some(token) = this.next_page_token.take() {
+                    this.in_flight = Some((this.fetch)(token, this.page_size));
+                }
+            }
+
+            let Some(in_flight) = this.in_flight.as_mut() else {
+                break;
+            };
+            match in_flight.as_mut().poll(cx) {
+                Poll::Ready(Ok(page)) => {
+                    this.in_flight = None;
+                    // An empty page with a token would otherwise be requested
+                    // forever.
+                    if !page.next_page_token.is_empty() && !page.items.is_empty() {
+                        this.next_page_token = Some(page.next_page_token);
+                    }
+                    this.pages.push_back(page.items.into());
+                }
+                Poll::Ready(Err(e)) => {
+                    this.in_flight = None;
+                    this.error = Some(e);
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        while let Some(page) = this.pages.front_mut() {
+            match page.pop_front() {
+                Some(item) => return Poll::Ready(Some(Ok(item))),
+                None => {
+                    this.pages.pop_front();
+                }
+            }
+        }
+
+        if let Some(e) = this.error.take() {
+            return Poll::Ready(Some(Err(e)));
+        }
+
+        if this.in_flight.is_some() {
+            Poll::Pending
+        } else {
+            Poll::Ready(None)
+        }
+    }
+}
+
+impl<T> fmt::Debug for PageStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PageStream")
+            .field("page_size", &self.page_size)
+            .field("prefetch", &self.prefetch)
+            .field("next_page_token", &self.next_page_token)
+            .finish_non_exhaustive()
+    }
+}
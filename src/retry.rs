@@ -0,0 +1,168 @@
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_BACKOFF: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(2);
+const DEFAULT_MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Controls how requests are retried after transient failures.
+///
+/// Only idempotent calls are retried: `GET`, `PUT` and `DELETE` requests and
+/// the evaluation endpoints. Backoff grows exponentially from `base_backoff`
+/// up to `max_backoff`; a `Retry-After` header on a 429 or 503 response takes
+/// precedence over the computed delay. When the server asks to wait longer
+/// than `max_retry_after`, the response is returned without retrying.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_backoff: Duration,
+    max_backoff: Duration,
+    max_retry_after: Duration,
+    jitter: bool,
+    retry_statuses: Vec<StatusCode>,
+    retry_connect_errors: bool,
+    retry_timeouts: bool,
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            max_retry_after: DEFAULT_MAX_RETRY_AFTER,
+            jitter: true,
+            retry_statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_connect_errors: true,
+            retry_timeouts: true,
+        }
+    }
+
+    /// A policy that makes a single attempt per request.
+    pub fn disabled() -> Self {
+        Self::new().set_max_attempts(1)
+    }
+
+    /// Total number of attempts, including the first one.
+    pub fn set_max_attempts(mut self, v: u32) -> Self {
+        self.max_attempts = v.max(1);
+        self
+    }
+
+    pub fn set_base_backoff(mut self, v: Duration) -> Self {
+        self.base_backoff = v;
+        self
+    }
+
+    pub fn set_max_backoff(mut self, v: Duration) -> Self {
+        self.max_backoff = v;
+        self
+    }
+
+    /// Longest `Retry-After` delay to wait for. Defaults to 30 seconds.
+    pub fn set_max_retry_after(mut self, v: Duration) -> Self {
+        self.max_retry_after = v;
+        self
+    }
+
+    /// Randomize each delay between zero and the computed backoff.
+    pub fn set_jitter(mut self, v: bool) -> Self {
+        self.jitter = v;
+        self
+    }
+
+    pub fn set_retry_statuses(mut self, v: Vec<StatusCode>) -> Self {
+        self.retry_statuses = v;
+        self
+    }
+
+    pub fn set_retry_connect_errors(mut self, v: bool) -> Self {
+        self.retry_connect_errors = v;
+        self
+    }
+
+    pub fn set_retry_timeouts(mut self, v: bool) -> Self {
+        self.retry_timeouts = v;
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub fn max_retry_after(&self) -> Duration {
+        self.max_retry_after
+    }
+
+    pub(crate) fn should_retry_status(&self, status: StatusCode) -> bool {
+        self.retry_statuses.contains(&status)
+    }
+
+    pub(crate) fn should_retry_error(&self, err: &Error) -> bool {
+        match err {
+            Error::Request(e) => self.retry_connect_errors && e.is_connect(),
+            Error::Timeout(None) => self.retry_timeouts,
+            _ => false,
+        }
+    }
+
+    /// Delay to wait before the attempt following `attempt` (1-based).
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        let backoff = self
+            .base_backoff
+            .saturating_mul(1 << exp)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            backoff.mul_f64(random_fraction())
+        } else {
+            backoff
+        }
+    }
+
+    /// Delay requested by the server through `Retry-After`, either as a number
+    /// of seconds or as an HTTP date.
+    pub(crate) fn retry_after(&self, status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+        if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE {
+            return None;
+        }
+
+        let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+        if let Ok(secs) = value.parse::<u64>() {
+            return Some(Duration::from_secs(secs));
+        }
+
+        let date = DateTime::parse_from_rfc2822(value).ok()?;
+        Some(
+            (date.with_timezone(&Utc) - Utc::now())
+                .to_std()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A uniformly distributed value in `[0, 1)`, or 1 (no jitter) if the
+/// system random number generator is unavailable.
+fn random_fraction() -> f64 {
+    let mut bytes = [0; 8];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        return 1.0;
+    }
+    (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
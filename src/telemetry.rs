@@ -0,0 +1,318 @@
+//! Request instrumentation. Everything in here compiles to no-ops unless the
+//! `tracing` or `metrics` feature is enabled.
+
+use crate::error::{Error, Result};
+use crate::evaluation::{BooleanEvaluation, ErrorEvaluation, Reason, VariantEvaluation};
+use crate::transport::Route;
+use reqwest::{Method, StatusCode};
+use std::future::Future;
+#[cfg(feature = "metrics")]
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::Instant;
+
+#[cfg(feature = "tracing")]
+use tracing::{field, Instrument};
+
+/// Provider name reported on `feature_flag` events.
+#[cfg(feature = "tracing")]
+const PROVIDER_NAME: &str = "flipt";
+
+#[cfg(feature = "metrics")]
+const REQUESTS_TOTAL: &str = "flipt_client_requests_total";
+#[cfg(feature = "metrics")]
+const REQUEST_DURATION_SECONDS: &str = "flipt_client_request_duration_seconds";
+#[cfg(feature = "metrics")]
+const EVALUATIONS_TOTAL: &str = "flipt_client_evaluations_total";
+
+/// Tracks a single transport call, including all of its retry attempts.
+pub(crate) struct RequestTelemetry {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "metrics")]
+    method: Method,
+    #[cfg(feature = "metrics")]
+    template: &'static str,
+    start: Instant,
+    #[cfg(feature = "metrics")]
+    status: AtomicU16,
+}
+
+impl RequestTelemetry {
+    #[cfg_attr(
+        not(any(feature = "tracing", feature = "metrics")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn start(method: &Method, route: &Route) -> Self {
+        #[cfg(feature = "metrics")]
+        describe_metrics();
+
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "flipt.request",
+                otel.name = format!("{method} {}", route.template),
+                otel.kind = "client",
+                otel.status_code = field::Empty,
+                http.request.method = %method,
+                url.template = route.template,
+                flipt.namespace_key = route.namespace_key.as_deref(),
+                feature_flag.key = route.flag_key.as_deref(),
+                http.response.status_code = field::Empty,
+                error.type = field::Empty,
+                duration_ms = field::Empty,
+            ),
+            #[cfg(feature = "metrics")]
+            method: method.clone(),
+            #[cfg(feature = "metrics")]
+            template: route.template,
+            start: Instant::now(),
+            #[cfg(feature = "metrics")]
+            status: AtomicU16::new(0),
+        }
+    }
+
+    pub(crate) async fn instrument<F: Future>(&self, fut: F) -> F::Output {
+        #[cfg(feature = "tracing")]
+        let fut = fut.instrument(self.span.clone());
+        fut.await
+    }
+
+    /// Records the status of the final response.
+    #[cfg_attr(
+        not(any(feature = "tracing", feature = "metrics")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn record_status(&self, status: StatusCode) {
+        #[cfg(feature = "metrics")]
+        self.status.store(status.as_u16(), Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        self.span
+            .record("http.response.status_code", status.as_u16());
+    }
+
+    #[cfg_attr(
+        not(any(feature = "tracing", feature = "metrics")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn finish<T>(self, result: &Result<T>) {
+        #[cfg_attr(
+            not(any(feature = "tracing", feature = "metrics")),
+            allow(unused_variables)
+        )]
+        let elapsed = self.start.elapsed();
+
+        #[cfg(feature = "tracing")]
+        {
+            self.span
+                .record("duration_ms", elapsed.as_secs_f64() * 1000.0);
+            if let Err(err) = result {
+                self.span.record("otel.status_code", "ERROR");
+                self.span.record("error.type", error_type(err));
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            let status = match self.status.load(Ordering::Relaxed) {
+                0 => "none".to_string(),
+                status => status.to_string(),
+            };
+            let outcome = match result {
+                Ok(_) => "ok",
+                Err(err) => error_type(err),
+            };
+            metrics::counter!(
+                REQUESTS_TOTAL,
+                "endpoint" => self.template,
+                "method" => self.method.to_string(),
+                "status" => status,
+                "outcome" => outcome,
+            )
+            .increment(1);
+            metrics::histogram!(
+                REQUEST_DURATION_SECONDS,
+                "endpoint" => self.template,
+                "method" => self.method.to_string(),
+            )
+            .record(elapsed.as_secs_f64());
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn describe_metrics() {
+    static DESCRIBE: std::sync::Once = std::sync::Once::new();
+    DESCRIBE.call_once(|| {
+        metrics::describe_counter!(REQUESTS_TOTAL, "Requests sent to Flipt.");
+        metrics::describe_histogram!(
+            REQUEST_DURATION_SECONDS,
+            metrics::Unit::Seconds,
+            "Latency of requests sent to Flipt, including retries."
+        );
+        metrics::describe_counter!(EVALUATIONS_TOTAL, "Flag evaluations returned by Flipt.");
+    });
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+fn count_evaluation(namespace_key: &str, flag_key: &str, reason: &'static str, is_match: bool) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(
+        EVALUATIONS_TOTAL,
+        "namespace" => namespace_key.to_string(),
+        "flag_key" => flag_key.to_string(),
+        "reason" => reason,
+        "match" => if is_match { "true" } else { "false" },
+    )
+    .increment(1);
+}
+
+/// A short, stable name for the kind of error, suitable as a label.
+#[cfg_attr(not(any(feature = "tracing", feature = "metrics")), allow(dead_code))]
+pub(crate) fn error_type(err: &Error) -> &'static str {
+    match err {
+        Error::NotFound(_) => "not_found",
+        Error::Unauthenticated(_) => "unauthenticated",
+        Error::PermissionDenied(_) => "permission_denied",
+        Error::InvalidArgument(_) => "invalid_argument",
+        Error::AlreadyExists(_) => "already_exists",
+        Error::Unavailable(_) => "unavailable",
+        Error::Timeout(_) => "timeout",
+        Error::Decode(_) => "decode",
+        Error::Upstream(_) => "upstream",
+        Error::Request(_) => "request",
+        Error::Config(_) => "config",
+        Error::Conflict(_) => "conflict",
+        Error::Login(_) => "login",
+        Error::Internal(_) => "internal",
+    }
+}
+
+/// Maps a Flipt reason onto the OpenTelemetry `feature_flag.evaluation.reason`
+/// values.
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+fn otel_reason(reason: &Reason) -> &'static str {
+    match reason {
+        Reason::Match => "targeting_match",
+        Reason::Default => "default",
+        Reason::FlagDisabled => "disabled",
+        Reason::Unspecified | Reason::Unknown(_) => "unknown",
+    }
+}
+
+/// The `reason` label used on evaluation metrics.
+fn reason_label(reason: &Reason) -> &'static str {
+    match reason {
+        Reason::Match => "match",
+        Reason::Default => "default",
+        Reason::FlagDisabled => "flag_disabled",
+        Reason::Unspecified | Reason::Unknown(_) => "unknown",
+    }
+}
+
+pub(crate) fn boolean_evaluated(namespace_key: &str, evaluation: &BooleanEvaluation) {
+    count_evaluation(
+        namespace_key,
+        &evaluation.flag_key,
+        reason_label(&evaluation.reason),
+        evaluation.reason == Reason::Match,
+    );
+    #[cfg(feature = "tracing")]
+    tracing::event!(
+        name: "feature_flag",
+        tracing::Level::INFO,
+        feature_flag.key = evaluation.flag_key.as_str(),
+        feature_flag.provider_name = PROVIDER_NAME,
+        feature_flag.variant = evaluation.enabled,
+        feature_flag.evaluation.reason = otel_reason(&evaluation.reason),
+        flipt.request_id = evaluation.request_id.as_str(),
+        flipt.namespace_key = namespace_key,
+        "feature_flag"
+    );
+}
+
+pub(crate) fn variant_evaluated(namespace_key: &str, evaluation: &VariantEvaluation) {
+    count_evaluation(
+        namespace_key,
+        &evaluation.flag_key,
+        reason_label(&evaluation.reason),
+        evaluation.is_match,
+    );
+    #[cfg(feature = "tracing")]
+    tracing::event!(
+        name: "feature_flag",
+        tracing::Level::INFO,
+        feature_flag.key = evaluation.flag_key.as_str(),
+        feature_flag.provider_name = PROVIDER_NAME,
+        feature_flag.variant = evaluation.variant_key.as_str(),
+        feature_flag.evaluation.reason = otel_reason(&evaluation.reason),
+        flipt.request_id = evaluation.request_id.as_str(),
+        flipt.namespace_key = namespace_key,
+        "feature_flag"
+    );
+}
+
+pub(crate) fn evaluation_failed(evaluation: &ErrorEvaluation) {
+    count_evaluation(
+        &evaluation.namespace_key,
+        &evaluation.flag_key,
+        "error",
+        false,
+    );
+    #[cfg(feature = "tracing")]
+    tracing::event!(
+        name: "feature_flag",
+        tracing::Level::INFO,
+        feature_flag.key = evaluation.flag_key.as_str(),
+        feature_flag.provider_name = PROVIDER_NAME,
+        feature_flag.evaluation.reason = "error",
+        error.type = match evaluation.reason {
+            crate::evaluation::ErrorEvaluationReason::NotFound => "flag_not_found",
+            _ => "general",
+        },
+        flipt.namespace_key = evaluation.namespace_key.as_str(),
+        "feature_flag"
+    );
+}
+
+/// Propagates the current trace to Flipt through the W3C `traceparent` and
+/// `tracestate` headers.
+#[cfg(feature = "opentelemetry")]
+#[derive(Debug)]
+pub(crate) struct TraceContext;
+
+#[cfg(feature = "opentelemetry")]
+#[async_trait::async_trait]
+impl crate::middleware::Middleware for TraceContext {
+    async fn handle(
+        &self,
+        mut request: reqwest::Request,
+        next: crate::middleware::Next<'_>,
+    ) -> Result<reqwest::Response> {
+        use opentelemetry::trace::TraceContextExt;
+        use reqwest::header::HeaderValue;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let context = tracing::Span::current().context();
+        let span = context.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            let traceparent = format!(
+                "00-{}-{}-{:02x}",
+                span_context.trace_id(),
+                span_context.span_id(),
+                span_context.trace_flags().to_u8()
+            );
+            let headers = request.headers_mut();
+            if let Ok(value) = HeaderValue::from_str(&traceparent) {
+                headers.insert("traceparent", value);
+            }
+            let tracestate = span_context.trace_state().header();
+            if !tracestate.is_empty() {
+                if let Ok(value) = HeaderValue::from_str(&tracestate) {
+                    headers.insert("tracestate", value);
+                }
+            }
+        }
+        next.run(request).await
+    }
+}
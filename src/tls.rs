@@ -0,0 +1,80 @@
+use crate::error::{Error, Result};
+use std::path::PathBuf;
+
+/// PEM-encoded material, given inline or as a file read when the client is
+/// built.
+#[derive(Debug, Clone)]
+pub enum Pem {
+    File(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+impl Pem {
+    fn read(&self) -> Result<Vec<u8>> {
+        match self {
+            Pem::File(path) => std::fs::read(path)
+                .map_err(|e| Error::Config(format!("failed to read {}: {e}", path.display()))),
+            Pem::Bytes(bytes) => Ok(bytes.clone()),
+        }
+    }
+}
+
+/// TLS settings for connections to Flipt.
+///
+/// The built-in web PKI roots are always trusted; root certificates added
+/// here are trusted in addition to them.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    root_certificates: Vec<Pem>,
+    client_identity: Option<(Pem, Pem)>,
+    insecure_skip_verify: bool,
+}
+
+impl TlsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts the certificates in a PEM bundle, such as an internal CA.
+    pub fn add_root_certificate(mut self, v: Pem) -> Self {
+        self.root_certificates.push(v);
+        self
+    }
+
+    /// Presents a client certificate and its private key for mutual TLS.
+    pub fn set_client_identity(mut self, cert: Pem, key: Pem) -> Self {
+        self.client_identity = Some((cert, key));
+        self
+    }
+
+    /// Disables certificate and hostname verification. Only meant for local
+    /// development against self-signed servers.
+    pub fn set_insecure_skip_verify(mut self, v: bool) -> Self {
+        self.insecure_skip_verify = v;
+        self
+    }
+
+    pub(crate) fn apply(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder> {
+        for pem in &self.root_certificates {
+            let certs = reqwest::Certificate::from_pem_bundle(&pem.read()?)
+                .map_err(|e| Error::Config(format!("invalid root certificate: {e}")))?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        if let Some((cert, key)) = &self.client_identity {
+            let mut pem = cert.read()?;
+            pem.push(b'\n');
+            pem.extend(key.read()?);
+            let identity = reqwest::Identity::from_pem(&pem)
+                .map_err(|e| Error::Config(format!("invalid client identity: {e}")))?;
+            builder = builder.identity(identity);
+        }
+
+        Ok(builder.danger_accept_invalid_certs(self.insecure_skip_verify))
+    }
+}
@@ -0,0 +1,342 @@
+use crate::credentials::Authenticator;
+use crate::error::{DecodeError, Error, ErrorResponse, UpstreamError};
+use crate::middleware::{Middleware, Next};
+use crate::retry::RetryPolicy;
+use crate::telemetry::RequestTelemetry;
+use crate::{AuthScheme, Config, HttpVersion};
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+pub use crate::error::Result;
+
+/// A request path together with the template it was built from and the
+/// resources it targets, used to label telemetry.
+#[derive(Debug, Clone)]
+pub(crate) struct Route {
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    pub(crate) template: &'static str,
+    pub(crate) path: String,
+    pub(crate) namespace_key: Option<String>,
+    pub(crate) flag_key: Option<String>,
+}
+
+impl Route {
+    pub(crate) fn new(template: &'static str) -> Self {
+        Self {
+            template,
+            path: template.to_string(),
+            namespace_key: None,
+            flag_key: None,
+        }
+    }
+
+    /// Substitutes `{name}` in the path.
+    pub(crate) fn param(mut self, name: &str, value: &str) -> Self {
+        self.path = self.path.replace(&format!("{{{name}}}"), value);
+        self
+    }
+
+    pub(crate) fn namespace(mut self, key: &str) -> Self {
+        self.namespace_key = Some(key.to_string());
+        self.param("namespace_key", key)
+    }
+
+    pub(crate) fn flag(mut self, key: &str) -> Self {
+        self.flag_key = Some(key.to_string());
+        self.param("flag_key", key)
+    }
+}
+
+/// Per-call settings layered on top of the transport configuration.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CallOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) namespace_key: Option<String>,
+    pub(crate) headers: HeaderMap,
+}
+
+/// HTTP transport shared by every client family. Cloning is cheap and the
+/// clones share the same underlying connection pool.
+#[derive(Debug, Clone)]
+pub(crate) struct Transport {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    client: reqwest::Client,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    authenticator: Option<Arc<Authenticator>>,
+    endpoint: Url,
+    retry_policy: RetryPolicy,
+    request_timeout: Option<Duration>,
+    namespace: String,
+}
+
+impl Transport {
+    pub(crate) fn new(config: Config) -> Result<Self> {
+        let mut builder = reqwest::Client::builder().user_agent(config.user_agent);
+        if let Some(timeout) = config.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = config.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(timeout) = config.request_timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder = builder
+            .gzip(config.gzip)
+            .brotli(config.brotli)
+            .tcp_keepalive(config.tcp_keepalive);
+        builder = match config.http_version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1Only => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+        if let Some(timeout) = config.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = config.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        let client = config.tls.apply(builder)?.build()?;
+
+        let mut middlewares: Vec<Arc<dyn Middleware>> = Vec::new();
+        #[cfg(feature = "opentelemetry")]
+        middlewares.push(Arc::new(crate::telemetry::TraceContext));
+        let builtin = middlewares.len();
+        middlewares.extend(config.middlewares);
+
+        // Credential exchanges, such as for Kubernetes service account
+        // tokens, go through the same middlewares but are not authenticated
+        // themselves.
+        let exchange = Self {
+            inner: Arc::new(Inner {
+                client: client.clone(),
+                middlewares: middlewares.clone(),
+                authenticator: None,
+                endpoint: config.endpoint.clone(),
+                retry_policy: config.retry_policy.clone(),
+                request_timeout: config.request_timeout,
+                namespace: config.namespace.clone(),
+            }),
+        };
+        let authenticator = Arc::new(Authenticator::new(config.auth_scheme, exchange));
+        middlewares.insert(builtin, authenticator.clone());
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                client,
+                middlewares,
+                authenticator: Some(authenticator),
+                endpoint: config.endpoint,
+                retry_policy: config.retry_policy,
+                request_timeout: config.request_timeout,
+                namespace: config.namespace,
+            }),
+        })
+    }
+
+    pub(crate) fn set_auth_scheme(&self, auth_scheme: AuthScheme) {
+        if let Some(authenticator) = &self.inner.authenticator {
+            authenticator.set_auth_scheme(auth_scheme);
+        }
+    }
+
+    /// The namespace to use for a request: the one set on the request, then
+    /// the one the sub-client is scoped to, then the configured default.
+    pub(crate) fn namespace_or<'a>(
+        &'a self,
+        namespace_key: &'a Option<String>,
+        options: &'a CallOptions,
+    ) -> &'a str {
+        namespace_key
+            .as_deref()
+            .or(options.namespace_key.as_deref())
+            .unwrap_or(&self.inner.namespace)
+    }
+
+    pub(crate) async fn get<P, R>(
+        &self,
+        route: Route,
+        params: Option<&P>,
+        options: &CallOptions,
+    ) -> Result<R>
+    where
+        P: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        self.execute(Method::GET, route, params, None::<&()>, true, options)
+            .await
+    }
+
+    pub(crate) async fn post<B, R>(
+        &self,
+        route: Route,
+        body: Option<&B>,
+        options: &CallOptions,
+    ) -> Result<R>
+    where
+        B: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        self.execute(Method::POST, route, None::<&()>, body, false, options)
+            .await
+    }
+
+    /// Like `post`, for endpoints that have no side effects (such as
+    /// evaluation) and can safely be retried.
+    pub(crate) async fn post_idempotent<B, R>(
+        &self,
+        route: Route,
+        body: Option<&B>,
+        options: &CallOptions,
+    ) -> Result<R>
+    where
+        B: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        self.execute(Method::POST, route, None::<&()>, body, true, options)
+            .await
+    }
+
+    pub(crate) async fn put<B, R>(
+        &self,
+        route: Route,
+        body: Option<&B>,
+        options: &CallOptions,
+    ) -> Result<R>
+    where
+        B: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        self.execute(Method::PUT, route, None::<&()>, body, true, options)
+            .await
+    }
+
+    pub(crate) async fn delete<P, R>(
+        &self,
+        route: Route,
+        params: Option<&P>,
+        options: &CallOptions,
+    ) -> Result<R>
+    where
+        P: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        self.execute(Method::DELETE, route, params, None::<&()>, true, options)
+            .await
+    }
+
+    async fn execute<P, B, R>(
+        &self,
+        method: Method,
+        route: Route,
+        params: Option<&P>,
+        body: Option<&B>,
+        idempotent: bool,
+        options: &CallOptions,
+    ) -> Result<R>
+    where
+        P: serde::Serialize,
+        B: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        let url = self.build_url(&route.path)?;
+        let telemetry = RequestTelemetry::start(&method, &route);
+        let mut request = self.inner.client.request(method, url);
+        if let Some(timeout) = options.timeout {
+            request = request.timeout(timeout);
+        }
+        if !options.headers.is_empty() {
+            request = request.headers(options.headers.clone());
+        }
+        if let Some(params) = params {
+            request = request.query(params);
+        }
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .json(body);
+        }
+        // The timeouts set on the request bound each attempt; this bounds the
+        // call as a whole, including retries and the backoff between them.
+        let deadline = options.timeout.or(self.inner.request_timeout);
+        let result = telemetry
+            .instrument(async {
+                let call = async {
+                    let resp = self.send(request, idempotent).await?;
+                    telemetry.record_status(resp.status());
+                    deserialize(resp).await
+                };
+                match deadline {
+                    Some(deadline) => tokio::time::timeout(deadline, call)
+                        .await
+                        .unwrap_or(Err(Error::Timeout(None))),
+                    None => call.await,
+                }
+            })
+            .await;
+        telemetry.finish(&result);
+        result
+    }
+
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        idempotent: bool,
+    ) -> Result<reqwest::Response> {
+        let request = request.build()?;
+        let next = Next::new(&self.inner.client, &self.inner.middlewares);
+        let retry_policy = &self.inner.retry_policy;
+
+        let mut attempt = 1;
+        loop {
+            let retryable = idempotent && attempt < retry_policy.max_attempts();
+            let attempt_request = match request.try_clone() {
+                Some(r) if retryable => r,
+                _ => return next.run(request).await,
+            };
+
+            let delay = match next.run(attempt_request).await {
+                Ok(resp) if retry_policy.should_retry_status(resp.status()) => {
+                    match retry_policy.retry_after(resp.status(), resp.headers()) {
+                        Some(delay) if delay > retry_policy.max_retry_after() => return Ok(resp),
+                        Some(delay) => delay,
+                        None => retry_policy.backoff(attempt),
+                    }
+                }
+                Err(err) if retry_policy.should_retry_error(&err) => retry_policy.backoff(attempt),
+                result => return result,
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    fn build_url(&self, path: &str) -> Result<Url> {
+        Ok(self.inner.endpoint.join(path)?)
+    }
+}
+
+pub async fn deserialize<T: serde::de::DeserializeOwned>(resp: reqwest::Response) -> Result<T> {
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    let body = resp.bytes().await?;
+    let decode_error = |e| Error::Decode(Box::new(DecodeError::new(status, &body, e)));
+
+    if status.is_success() {
+        return serde_json::from_slice(&body).map_err(decode_error);
+    }
+    Err(Error::from_response(ErrorResponse {
+        status,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+        error: serde_json::from_slice::<UpstreamError>(&body).ok(),
+    }))
+}
@@ -0,0 +1,58 @@
+/// Defines an enum carried as a string on the wire.
+///
+/// Values this client does not know, such as ones added in a newer Flipt
+/// release, deserialize into `Unknown` and serialize back unchanged instead
+/// of failing the whole response.
+macro_rules! wire_enum {
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant:ident = $value:literal,
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $(
+                $(#[$variant_attr])*
+                $variant,
+            )*
+            /// A value this client does not know.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// The value as sent on the wire.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(v) => v,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(v: String) -> Self {
+                match v.as_str() {
+                    $($value => $name::$variant,)*
+                    _ => $name::Unknown(v),
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(v: $name) -> Self {
+                match v {
+                    $name::Unknown(v) => v,
+                    v => v.as_str().into(),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use wire_enum;
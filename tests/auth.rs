@@ -0,0 +1,126 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{client, StubResponse, StubServer};
+use flipt::auth::token::TokenListRequest;
+use flipt::auth::{ExpireSelfRequest, Method};
+use flipt::AuthScheme;
+
+fn authentication(method: &str) -> String {
+    format!(
+        r#"{{"id": "auth-a", "method": "{method}", "metadata": {{}},
+            "createdAt": "2023-01-01T00:00:00Z", "updatedAt": "2023-01-01T00:00:00Z"}}"#
+    )
+}
+
+#[tokio::test]
+async fn every_method() {
+    let methods = [
+        ("METHOD_NONE", Method::None),
+        ("METHOD_TOKEN", Method::Token),
+        ("METHOD_OIDC", Method::Oidc),
+        ("METHOD_KUBERNETES", Method::Kubernetes),
+        ("METHOD_GITHUB", Method::Github),
+        ("METHOD_JWT", Method::Jwt),
+        ("METHOD_CLOUD", Method::Unknown("METHOD_CLOUD".into())),
+    ];
+    let authentications: Vec<_> = methods.iter().map(|(m, _)| authentication(m)).collect();
+    let body = format!(
+        r#"{{"authentications": [{}], "nextPageToken": ""}}"#,
+        authentications.join(",")
+    );
+    let server = StubServer::start(move |_| StubResponse::json(200, &body)).await;
+
+    let list = client(&server, AuthScheme::BearerToken("secret".into()))
+        .auth()
+        .tokens()
+        .list(&TokenListRequest::default())
+        .await
+        .expect("list");
+
+    let got: Vec<_> = list.authentications.into_iter().map(|a| a.method).collect();
+    let want: Vec<_> = methods.into_iter().map(|(_, m)| m).collect();
+    assert_eq!(got, want);
+    assert_eq!(got[6].as_str(), "METHOD_CLOUD");
+}
+
+#[tokio::test]
+async fn me_with_jwt() {
+    let server =
+        StubServer::start(|_| StubResponse::json(200, &authentication("METHOD_JWT"))).await;
+
+    let me = client(&server, AuthScheme::BearerToken("secret".into()))
+        .auth()
+        .me()
+        .await
+        .expect("me");
+
+    assert_eq!(me.method, Method::Jwt);
+}
+
+#[tokio::test]
+async fn expire_self() {
+    let server = StubServer::start(|_| StubResponse::json(200, "{}")).await;
+    let client = client(&server, AuthScheme::BearerToken("secret".into()));
+
+    client
+        .auth()
+        .expire_self(&ExpireSelfRequest::default())
+        .await
+        .expect("expire now");
+    client
+        .auth()
+        .expire_self(&ExpireSelfRequest {
+            expires_at: Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+        })
+        .await
+        .expect("expire later");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(requests[0].path, "/auth/v1/self/expire");
+    assert_eq!(requests[0].header("authorization"), Some("Bearer secret"));
+    assert_eq!(requests[0].json(), serde_json::json!({}));
+    assert_eq!(
+        requests[1].json(),
+        serde_json::json!({"expiresAt": "2030-01-01T00:00:00Z"})
+    );
+}
+
+#[tokio::test]
+async fn methods() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            r#"{"methods": [
+                {"method": "METHOD_TOKEN", "enabled": true, "sessionCompatible": false},
+                {"method": "METHOD_OIDC", "enabled": true, "sessionCompatible": true,
+                 "metadata": {"providers": {"google": {"callbackUrl": "/callback"}}}},
+                {"method": "METHOD_GITHUB"}
+            ]}"#,
+        )
+    })
+    .await;
+
+    let list = client(&server, AuthScheme::BearerToken("secret".into()))
+        .auth()
+        .methods()
+        .await
+        .expect("methods");
+
+    assert_eq!(server.requests()[0].path, "/auth/v1/method");
+    let enabled: Vec<_> = list
+        .methods
+        .iter()
+        .map(|m| (m.method.clone(), m.enabled, m.session_compatible))
+        .collect();
+    assert_eq!(
+        enabled,
+        vec![
+            (Method::Token, true, false),
+            (Method::Oidc, true, true),
+            (Method::Github, false, false),
+        ]
+    );
+    assert!(list.methods[1].metadata.contains_key("providers"));
+}
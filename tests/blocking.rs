@@ -0,0 +1,90 @@
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use common::{config, StubResponse, StubServer, FLAG};
+use flipt::api::flag::FlagGetRequest;
+use flipt::blocking::FliptClient;
+use flipt::error::Error;
+use flipt::evaluation::EvaluateRequest;
+use flipt::AuthScheme;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const BOOLEAN_EVALUATION: &str = r#"{
+    "enabled": true,
+    "reason": "MATCH_EVALUATION_REASON",
+    "requestId": "request-a",
+    "requestDurationMillis": 1.0,
+    "timestamp": "2023-01-01T00:00:00Z",
+    "flagKey": "flag-a"
+}"#;
+
+/// Starts a stub server on its own runtime, which must outlive the test.
+fn start(response: StubResponse) -> (Runtime, StubServer) {
+    let runtime = Runtime::new().expect("runtime");
+    let server = runtime.block_on(StubServer::start(move |_| response.clone()));
+    (runtime, server)
+}
+
+/// The blocking counterpart of [`common::client`].
+fn client(server: &StubServer) -> FliptClient {
+    FliptClient::new(config(server, AuthScheme::BearerToken("secret".into())))
+        .expect("build client")
+}
+
+#[test]
+fn get_flag() {
+    let (_runtime, server) = start(StubResponse::json(200, FLAG));
+
+    let flag = client(&server)
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            namespace_key: None,
+            key: "flag-a".into(),
+        })
+        .expect("get flag");
+
+    assert_eq!(flag.key, "flag-a");
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/api/v1/namespaces/default/flags/flag-a");
+    assert_eq!(requests[0].header("authorization"), Some("Bearer secret"));
+}
+
+#[test]
+fn evaluate_boolean() {
+    let (_runtime, server) = start(StubResponse::json(200, BOOLEAN_EVALUATION));
+
+    let evaluation = client(&server)
+        .evaluation()
+        .boolean(&EvaluateRequest {
+            namespace_key: "default".into(),
+            flag_key: "flag-a".into(),
+            entity_id: "entity".into(),
+            ..Default::default()
+        })
+        .expect("evaluate");
+
+    assert!(evaluation.enabled);
+    assert_eq!(server.requests()[0].path, "/evaluate/v1/boolean");
+}
+
+#[test]
+fn errors_and_timeouts() {
+    let (_runtime, server) = start(StubResponse::json(
+        404,
+        r#"{"code": 5, "message": "not found", "details": []}"#,
+    ));
+    let err = client(&server).auth().tokens().get("id").expect_err("get");
+    assert!(matches!(err, Error::NotFound(_)), "{err:?}");
+
+    let (_runtime, server) = start(StubResponse::json(200, "{}").delay(Duration::from_secs(1)));
+    let err = client(&server)
+        .meta()
+        .info()
+        .with_timeout(Duration::from_millis(100))
+        .get()
+        .expect_err("get info");
+    assert!(matches!(err, Error::Timeout(None)), "{err:?}");
+}
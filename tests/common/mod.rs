@@ -0,0 +1,236 @@
+#![allow(dead_code)]
+
+use flipt::retry::RetryPolicy;
+use flipt::{AuthScheme, Config, FliptClient};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use url::Url;
+
+/// A variant flag `flag-a` in the `default` namespace.
+pub const FLAG: &str = r#"{
+    "namespaceKey": "default",
+    "key": "flag-a",
+    "name": "Flag A",
+    "description": "",
+    "enabled": true,
+    "type": "VARIANT_FLAG_TYPE",
+    "createdAt": "2023-01-01T00:00:00Z",
+    "updatedAt": "2023-01-01T00:00:00Z",
+    "variants": []
+}"#;
+
+/// Config for talking to `server`, without retries so failures surface
+/// straight away.
+pub fn config(server: &StubServer, auth_scheme: AuthScheme) -> Config {
+    Config::new(server.endpoint(), auth_scheme).set_retry_policy(RetryPolicy::disabled())
+}
+
+pub fn client(server: &StubServer, auth_scheme: AuthScheme) -> FliptClient {
+    FliptClient::new(config(server, auth_scheme)).expect("build client")
+}
+
+/// A request received by [`StubServer`].
+#[derive(Debug, Clone)]
+pub struct StubRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl StubRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).expect("request body is json")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StubResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    delay: Option<Duration>,
+}
+
+impl StubResponse {
+    pub fn new(status: u16, body: &str) -> Self {
+        Self::bytes(status, body.as_bytes().to_vec())
+    }
+
+    pub fn bytes(status: u16, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+            delay: None,
+        }
+    }
+
+    pub fn json(status: u16, body: &str) -> Self {
+        Self::new(status, body).header("Content-Type", "application/json")
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Waits for `delay` before sending the response.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+type Handler = dyn Fn(&StubRequest) -> StubResponse + Send + Sync;
+
+/// Minimal HTTP/1.1 server answering every request with `handler`.
+pub struct StubServer {
+    endpoint: Url,
+    requests: Arc<Mutex<Vec<StubRequest>>>,
+}
+
+impl StubServer {
+    pub async fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&StubRequest) -> StubResponse + Send + Sync + 'static,
+    {
+        Self::listen(None, handler).await
+    }
+
+    /// Like [`StubServer::start`], serving HTTPS on `localhost` with `acceptor`.
+    pub async fn start_tls<F>(acceptor: TlsAcceptor, handler: F) -> Self
+    where
+        F: Fn(&StubRequest) -> StubResponse + Send + Sync + 'static,
+    {
+        Self::listen(Some(acceptor), handler).await
+    }
+
+    async fn listen<F>(tls: Option<TlsAcceptor>, handler: F) -> Self
+    where
+        F: Fn(&StubRequest) -> StubResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+        let endpoint = match tls {
+            Some(_) => Url::parse(&format!("https://localhost:{}", addr.port())),
+            None => Url::parse(&format!("http://{addr}")),
+        }
+        .expect("endpoint");
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let handler = handler.clone();
+                let recorded = recorded.clone();
+                let tls = tls.clone();
+                tokio::spawn(async move {
+                    let _ = match tls {
+                        Some(acceptor) => match acceptor.accept(stream).await {
+                            Ok(stream) => serve(stream, handler, recorded).await,
+                            Err(e) => Err(e),
+                        },
+                        None => serve(stream, handler, recorded).await,
+                    };
+                });
+            }
+        });
+
+        Self { endpoint, requests }
+    }
+
+    pub fn endpoint(&self) -> Url {
+        self.endpoint.clone()
+    }
+
+    pub fn requests(&self) -> Vec<StubRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn serve<S>(
+    mut stream: S,
+    handler: Arc<Handler>,
+    recorded: Arc<Mutex<Vec<StubRequest>>>,
+) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut buf = Vec::new();
+    let header_end = loop {
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+
+    let request = StubRequest {
+        method,
+        path,
+        headers,
+        body,
+    };
+    let response = handler(&request);
+    recorded.lock().unwrap().push(request);
+
+    if let Some(delay) = response.delay {
+        tokio::time::sleep(delay).await;
+    }
+
+    let mut out = format!("HTTP/1.1 {} Stub\r\n", response.status);
+    for (k, v) in &response.headers {
+        out.push_str(&format!("{k}: {v}\r\n"));
+    }
+    out.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.body.len()
+    ));
+    stream.write_all(out.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await
+}
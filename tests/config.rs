@@ -0,0 +1,238 @@
+#![cfg(feature = "config_file")]
+
+mod common;
+
+use common::{StubResponse, StubServer, FLAG};
+use flipt::api::flag::FlagGetRequest;
+use flipt::error::Error;
+use flipt::{AuthScheme, Config, FliptClient};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Serializes the tests that change `FLIPT_*` environment variables.
+static ENV: Mutex<()> = Mutex::new(());
+
+fn write_temp(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("flipt-config-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Sends a flag request without a namespace and returns what the server saw.
+async fn get_flag(server: &StubServer, config: Config) -> common::StubRequest {
+    let client = FliptClient::new(config).expect("build client");
+    client
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            namespace_key: None,
+            key: "flag-a".into(),
+        })
+        .await
+        .expect("get flag");
+    server.requests().pop().expect("request")
+}
+
+#[tokio::test]
+async fn toml_file() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+    let file = write_temp(
+        "flipt.toml",
+        &format!(
+            r#"
+endpoint = "{}"
+namespace = "payments"
+user_agent = "billing/1.0"
+request_timeout = 5
+
+[auth]
+token = "secret"
+
+[retry]
+max_attempts = 2
+base_backoff = "10ms"
+"#,
+            server.endpoint()
+        ),
+    );
+
+    let config = Config::builder()
+        .set_env(false)
+        .set_file(file)
+        .build()
+        .expect("config");
+    let request = get_flag(&server, config).await;
+
+    assert_eq!(request.path, "/api/v1/namespaces/payments/flags/flag-a");
+    assert_eq!(request.header("authorization"), Some("Bearer secret"));
+    assert_eq!(request.header("user-agent"), Some("billing/1.0"));
+}
+
+#[tokio::test]
+async fn yaml_file_with_code_overrides() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+    let file = write_temp(
+        "flipt.yaml",
+        &format!(
+            "endpoint: {}\nnamespace: staging\nauth:\n  token: from-file\n",
+            server.endpoint()
+        ),
+    );
+
+    let config = Config::builder()
+        .set_env(false)
+        .set_file(file)
+        .set_namespace("payments")
+        .set_auth_scheme(AuthScheme::BearerToken("from-code".into()))
+        .build()
+        .expect("config");
+    let request = get_flag(&server, config).await;
+
+    assert_eq!(request.path, "/api/v1/namespaces/payments/flags/flag-a");
+    assert_eq!(request.header("authorization"), Some("Bearer from-code"));
+}
+
+#[tokio::test]
+async fn env_overrides_file() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+    let file = write_temp(
+        "env.toml",
+        "endpoint = \"http://unused.invalid\"\nnamespace = \"payments\"\n",
+    );
+
+    let config = {
+        let _env = ENV.lock().unwrap();
+        std::env::set_var("FLIPT_CONFIG_FILE", &file);
+        std::env::set_var("FLIPT_ENDPOINT", server.endpoint().as_str());
+        std::env::set_var("FLIPT_AUTH_TOKEN", "from-env");
+        let config = Config::builder().build();
+        std::env::remove_var("FLIPT_CONFIG_FILE");
+        std::env::remove_var("FLIPT_ENDPOINT");
+        std::env::remove_var("FLIPT_AUTH_TOKEN");
+        config
+    };
+
+    let request = get_flag(&server, config.expect("config")).await;
+    assert_eq!(request.path, "/api/v1/namespaces/payments/flags/flag-a");
+    assert_eq!(request.header("authorization"), Some("Bearer from-env"));
+}
+
+#[test]
+fn new_from_env_requires_endpoint() {
+    let _env = ENV.lock().unwrap();
+
+    let err = Config::new_from_env().expect_err("no endpoint");
+    assert!(matches!(err, Error::Config(_)), "{err:?}");
+    assert!(err.to_string().contains("FLIPT_ENDPOINT"), "{err}");
+
+    assert!(Config::builder().build().is_ok());
+}
+
+#[test]
+fn reports_every_problem() {
+    let file = write_temp(
+        "invalid.toml",
+        r#"
+endpoint = "ftp://flipt.internal"
+request_timeout = "soon"
+
+[auth]
+token = "secret"
+jwt = "header.claims.signature"
+
+[retry]
+max_attempts = 0
+
+[tls]
+client_cert_file = "/does/not/exist.pem"
+"#,
+    );
+
+    let err = Config::builder()
+        .set_env(false)
+        .set_file(&file)
+        .build()
+        .expect_err("invalid config");
+
+    let Error::Config(message) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert!(message.starts_with("6 problems:"), "{message}");
+    for problem in [
+        "invalid duration for request_timeout: \"soon\"",
+        "endpoint ftp://flipt.internal/ must use http or https",
+        "auth.token and auth.jwt cannot both be set",
+        "retry.max_attempts must be at least 1",
+        "/does/not/exist.pem does not exist",
+        "tls.client_cert_file and tls.client_key_file must be set together",
+    ] {
+        assert!(
+            message.contains(problem),
+            "missing {problem:?} in {message}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn http_settings() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+    let file = write_temp(
+        "http.toml",
+        &format!(
+            r#"
+endpoint = "{}"
+
+[http]
+gzip = false
+brotli = false
+version = "http1"
+pool_idle_timeout = "30s"
+pool_max_idle_per_host = 4
+tcp_keepalive = "1m"
+"#,
+            server.endpoint()
+        ),
+    );
+
+    let config = Config::builder()
+        .set_env(false)
+        .set_file(file)
+        .build()
+        .expect("config");
+    let request = get_flag(&server, config).await;
+
+    assert_eq!(request.header("accept-encoding"), None);
+}
+
+#[test]
+fn rejects_unknown_http_version() {
+    let file = write_temp("version.yaml", "http:\n  version: http3\n");
+
+    let err = Config::builder()
+        .set_env(false)
+        .set_file(&file)
+        .build()
+        .expect_err("invalid version");
+
+    assert!(
+        err.to_string()
+            .contains("invalid http version for http.version: \"http3\""),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejects_unknown_settings() {
+    let file = write_temp(
+        "unknown.yaml",
+        "endpoint: http://localhost:8080\ntimeout: 5s\n",
+    );
+
+    let err = Config::builder()
+        .set_env(false)
+        .set_file(&file)
+        .build()
+        .expect_err("unknown setting");
+
+    assert!(err.to_string().contains("unknown field `timeout`"), "{err}");
+}
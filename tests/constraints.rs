@@ -0,0 +1,104 @@
+mod common;
+
+use common::{client, StubResponse, StubServer};
+use flipt::api::constraint::{
+    ComparisonType, Constraint, ConstraintCreateRequest, Operator, ValueSet,
+};
+use flipt::error::Error;
+use flipt::AuthScheme;
+use serde_json::Number;
+
+fn constraint(operator: &str, comparison_type: &str, value: &str) -> String {
+    format!(
+        r#"{{"id": "c1", "operator": "{operator}", "property": "entityId",
+            "type": "{comparison_type}", "value": {value:?}, "description": "",
+            "createdAt": "2023-01-01T00:00:00Z", "updatedAt": "2023-01-01T00:00:00Z"}}"#
+    )
+}
+
+#[test]
+fn is_one_of_strings() {
+    let create = ConstraintCreateRequest::is_one_of("beta", "region", vec!["eu", "us"]);
+
+    assert_eq!(create.segment_key, "beta");
+    assert_eq!(create.operator, Operator::IsOneOf);
+    assert_eq!(create.comparison_type, ComparisonType::String);
+    assert_eq!(create.value, r#"["eu","us"]"#);
+}
+
+#[test]
+fn is_not_one_of_numbers() {
+    let create = ConstraintCreateRequest::is_not_one_of("beta", "age", vec![18, 21]);
+
+    assert_eq!(create.operator, Operator::IsNotOneOf);
+    assert_eq!(create.comparison_type, ComparisonType::Number);
+    assert_eq!(create.value, "[18,21]");
+}
+
+#[test]
+fn float_value_sets() {
+    let values = ValueSet::try_from(vec![1.5, 2.0]).expect("finite values");
+    let create = ConstraintCreateRequest::is_one_of("beta", "score", values);
+    assert_eq!(create.value, "[1.5,2.0]");
+
+    let err = ValueSet::try_from(vec![1.0, f64::NAN]).expect_err("NaN");
+    assert!(matches!(err, Error::InvalidArgument(_)), "{err:?}");
+    assert!(ValueSet::try_from(vec![f64::INFINITY]).is_err());
+}
+
+#[tokio::test]
+async fn entity_ids() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            &constraint(
+                "isoneof",
+                "ENTITY_ID_COMPARISON_TYPE",
+                r#"["user-1","user-2"]"#,
+            ),
+        )
+    })
+    .await;
+
+    let constraint = client(&server, AuthScheme::None)
+        .api()
+        .constraints()
+        .create(&ConstraintCreateRequest {
+            comparison_type: ComparisonType::EntityId,
+            ..ConstraintCreateRequest::is_one_of("vip", "entityId", vec!["user-1", "user-2"])
+        })
+        .await
+        .expect("create constraint");
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["operator"], "isoneof");
+    assert_eq!(body["type"], "ENTITY_ID_COMPARISON_TYPE");
+    assert_eq!(body["value"], r#"["user-1","user-2"]"#);
+    assert_eq!(constraint.comparison_type, ComparisonType::EntityId);
+    assert_eq!(
+        constraint.value_set(),
+        Some(ValueSet::Strings(vec!["user-1".into(), "user-2".into()]))
+    );
+}
+
+#[test]
+fn value_set_follows_comparison_type() {
+    let value_set = |operator, comparison_type, value| {
+        serde_json::from_str::<Constraint>(&constraint(operator, comparison_type, value))
+            .expect("constraint")
+            .value_set()
+    };
+
+    assert_eq!(
+        value_set("isnotoneof", "NUMBER_COMPARISON_TYPE", "[1, 2.5]"),
+        Some(ValueSet::Numbers(vec![
+            1.into(),
+            Number::from_f64(2.5).unwrap()
+        ]))
+    );
+    assert_eq!(
+        value_set("isoneof", "STRING_COMPARISON_TYPE", "eu,us"),
+        None
+    );
+    assert_eq!(value_set("eq", "STRING_COMPARISON_TYPE", r#"["eu"]"#), None);
+}
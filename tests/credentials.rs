@@ -0,0 +1,297 @@
+mod common;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, TimeDelta, Utc};
+use common::{client, StubResponse, StubServer};
+use flipt::credentials::{Bearer, Credential, CredentialProvider, StaticHeader};
+use flipt::error::{Error, Result};
+use flipt::AuthScheme;
+use futures_util::future::join_all;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const INFO: &str = r#"{"build": {"version": "v1.0.0"}}"#;
+
+fn authorizations(server: &StubServer) -> Vec<Option<String>> {
+    server
+        .requests()
+        .iter()
+        .map(|r| r.header("authorization").map(Into::into))
+        .collect()
+}
+
+fn jwt(expires_at: DateTime<Utc>) -> String {
+    let claims = format!(r#"{{"sub": "ci", "exp": {}}}"#, expires_at.timestamp());
+    format!(
+        "{}.{}.signature",
+        URL_SAFE_NO_PAD.encode(r#"{"alg": "RS256"}"#),
+        URL_SAFE_NO_PAD.encode(claims)
+    )
+}
+
+/// Issues `token` valid for an hour, after a delay.
+struct Slow {
+    calls: Arc<AtomicUsize>,
+    token: &'static str,
+}
+
+#[async_trait]
+impl CredentialProvider for Slow {
+    async fn credential(&self) -> Result<Credential> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        Ok(Credential::bearer(self.token)?.set_expires_at(Utc::now() + TimeDelta::hours(1)))
+    }
+}
+
+/// Issues `token-1`, `token-2`, ... valid for `ttl`.
+struct Counter {
+    calls: Arc<AtomicUsize>,
+    ttl: Option<TimeDelta>,
+}
+
+#[async_trait]
+impl CredentialProvider for Counter {
+    async fn credential(&self) -> Result<Credential> {
+        let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let credential = Credential::bearer(&format!("token-{n}"))?;
+        Ok(match self.ttl {
+            Some(ttl) => credential.set_expires_at(Utc::now() + ttl),
+            None => credential,
+        })
+    }
+}
+
+/// Issues a token without an expiry. Every call after the first waits at
+/// `barrier`, so they only finish when enough of them run at once.
+struct Rendezvous {
+    calls: AtomicUsize,
+    barrier: tokio::sync::Barrier,
+}
+
+#[async_trait]
+impl CredentialProvider for Rendezvous {
+    async fn credential(&self) -> Result<Credential> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) > 0 {
+            self.barrier.wait().await;
+        }
+        Credential::bearer("static")
+    }
+}
+
+#[tokio::test]
+async fn jwt_scheme() {
+    let server = StubServer::start(|_| StubResponse::json(200, INFO)).await;
+    let client = client(&server, AuthScheme::Jwt("header.claims.signature".into()));
+
+    client.meta().info().get().await.expect("get info");
+
+    assert_eq!(
+        authorizations(&server),
+        vec![Some("JWT header.claims.signature".into())]
+    );
+}
+
+#[test]
+fn jwt_expires_with_claims() {
+    let expires_at = DateTime::from_timestamp(Utc::now().timestamp() + 3600, 0).unwrap();
+
+    let credential = Credential::jwt(&jwt(expires_at)).unwrap();
+    assert_eq!(credential.expires_at(), Some(expires_at));
+
+    let credential = Credential::jwt("not-a-jwt").unwrap();
+    assert_eq!(credential.expires_at(), None);
+}
+
+#[tokio::test]
+async fn static_header() {
+    let server = StubServer::start(|_| StubResponse::json(200, INFO)).await;
+    let client = client(
+        &server,
+        AuthScheme::provider(StaticHeader::new("X-Api-Key", "secret")),
+    );
+
+    client.meta().info().get().await.expect("get info");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].header("x-api-key"), Some("secret"));
+    assert_eq!(requests[0].header("authorization"), None);
+}
+
+#[tokio::test]
+async fn caches_until_expiry() {
+    let server = StubServer::start(|_| StubResponse::json(200, INFO)).await;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = client(
+        &server,
+        AuthScheme::provider(Counter {
+            calls: calls.clone(),
+            ttl: Some(TimeDelta::hours(1)),
+        }),
+    );
+
+    client.meta().info().get().await.expect("get info");
+    client.meta().info().get().await.expect("get info");
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        authorizations(&server),
+        vec![Some("Bearer token-1".into()), Some("Bearer token-1".into())]
+    );
+}
+
+#[tokio::test]
+async fn refreshes_expiring_credentials() {
+    let server = StubServer::start(|_| StubResponse::json(200, INFO)).await;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = client(
+        &server,
+        AuthScheme::provider(Counter {
+            calls: calls.clone(),
+            // Within the refresh margin, so never reused.
+            ttl: Some(TimeDelta::seconds(5)),
+        }),
+    );
+
+    client.meta().info().get().await.expect("get info");
+    client.meta().info().get().await.expect("get info");
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn refreshes_after_unauthorized() {
+    let server = StubServer::start(|req| match req.header("authorization") {
+        Some("Bearer token-1") => StubResponse::json(401, r#"{"code": 16, "message": "expired"}"#),
+        _ => StubResponse::json(200, INFO),
+    })
+    .await;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = client(
+        &server,
+        AuthScheme::provider(Counter {
+            calls: calls.clone(),
+            ttl: Some(TimeDelta::hours(1)),
+        }),
+    );
+
+    let err = client.meta().info().get().await.expect_err("revoked");
+    assert!(matches!(err, Error::Unauthenticated(_)), "{err:?}");
+    client.meta().info().get().await.expect("get info");
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn bearer_from_file_rotates() {
+    let server = StubServer::start(|_| StubResponse::json(200, INFO)).await;
+    let path: PathBuf = std::env::temp_dir().join(format!("flipt-token-{}", std::process::id()));
+    std::fs::write(&path, "first\n").unwrap();
+    let client = client(&server, AuthScheme::provider(Bearer::from_file(&path)));
+
+    client.meta().info().get().await.expect("get info");
+    std::fs::write(&path, "second\n").unwrap();
+    client.meta().info().get().await.expect("get info");
+
+    assert_eq!(
+        authorizations(&server),
+        vec![Some("Bearer first".into()), Some("Bearer second".into())]
+    );
+}
+
+#[tokio::test]
+async fn swap_on_live_client() {
+    let server = StubServer::start(|_| StubResponse::json(200, INFO)).await;
+    let client = client(&server, AuthScheme::BearerToken("old".into()));
+    let info = client.meta().info();
+
+    info.get().await.expect("get info");
+    client.set_auth_scheme(AuthScheme::BearerToken("new".into()));
+    info.get().await.expect("get info");
+    client.set_auth_scheme(AuthScheme::None);
+    info.get().await.expect("get info");
+
+    assert_eq!(
+        authorizations(&server),
+        vec![Some("Bearer old".into()), Some("Bearer new".into()), None]
+    );
+}
+
+#[tokio::test]
+async fn concurrent_requests_share_one_fetch() {
+    let server = StubServer::start(|_| StubResponse::json(200, INFO)).await;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = client(
+        &server,
+        AuthScheme::provider(Slow {
+            calls: calls.clone(),
+            token: "shared",
+        }),
+    );
+    let info = client.meta().info();
+
+    let results = join_all((0..5).map(|_| info.get())).await;
+
+    assert!(results.iter().all(|r| r.is_ok()));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        authorizations(&server),
+        vec![Some("Bearer shared".into()); 5]
+    );
+}
+
+#[tokio::test]
+async fn swap_during_fetch_is_not_cached() {
+    let server = StubServer::start(|_| StubResponse::json(200, INFO)).await;
+    let client = client(
+        &server,
+        AuthScheme::provider(Slow {
+            calls: Arc::new(AtomicUsize::new(0)),
+            token: "old",
+        }),
+    );
+
+    let in_flight = tokio::spawn({
+        let client = client.clone();
+        async move { client.meta().info().get().await }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    client.set_auth_scheme(AuthScheme::BearerToken("new".into()));
+    in_flight.await.unwrap().expect("get info");
+    client.meta().info().get().await.expect("get info");
+
+    assert_eq!(
+        authorizations(&server),
+        vec![Some("Bearer old".into()), Some("Bearer new".into())]
+    );
+}
+
+#[tokio::test]
+async fn credentials_without_expiry_are_fetched_concurrently() {
+    let server = StubServer::start(|_| StubResponse::json(200, INFO)).await;
+    let client = client(
+        &server,
+        AuthScheme::provider(Rendezvous {
+            calls: AtomicUsize::new(0),
+            barrier: tokio::sync::Barrier::new(2),
+        }),
+    );
+    let info = client.meta().info();
+    info.get().await.expect("get info");
+
+    // Both fetches have to be in progress at once to pass the barrier.
+    let results =
+        tokio::time::timeout(Duration::from_secs(5), join_all((0..2).map(|_| info.get())))
+            .await
+            .expect("fetches are not serialized");
+
+    assert!(results.iter().all(|r| r.is_ok()));
+    assert_eq!(
+        authorizations(&server),
+        vec![Some("Bearer static".into()); 3]
+    );
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::{client, StubResponse, StubServer};
+use flipt::api::constraint::{ComparisonType, ConstraintCreateRequest, Operator};
+use flipt::api::rollout::{RolloutGetRequest, RolloutType};
+use flipt::evaluation::{EvaluateRequest, Reason};
+use flipt::AuthScheme;
+
+#[tokio::test]
+async fn unknown_values_are_kept() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            r#"{"id": "r1", "rank": 1, "type": "PERCENTAGE_ROLLOUT_TYPE", "description": "",
+                "createdAt": "2023-01-01T00:00:00Z", "updatedAt": "2023-01-01T00:00:00Z"}"#,
+        )
+    })
+    .await;
+
+    let rollout = client(&server, AuthScheme::None)
+        .api()
+        .rollouts()
+        .get(&RolloutGetRequest {
+            id: "r1".into(),
+            flag_key: "flag1".into(),
+            ..Default::default()
+        })
+        .await
+        .expect("get");
+
+    assert_eq!(
+        rollout.rollout_type,
+        RolloutType::Unknown("PERCENTAGE_ROLLOUT_TYPE".into())
+    );
+    assert_eq!(rollout.rollout_type.as_str(), "PERCENTAGE_ROLLOUT_TYPE");
+}
+
+#[tokio::test]
+async fn unspecified_is_not_unknown() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            r#"{"enabled": false, "reason": "UNKNOWN_EVALUATION_REASON", "requestId": "r",
+                "requestDurationMillis": 1.0, "timestamp": "2023-01-01T00:00:00Z",
+                "flagKey": "flag1"}"#,
+        )
+    })
+    .await;
+
+    let evaluation = client(&server, AuthScheme::None)
+        .evaluation()
+        .boolean(&EvaluateRequest {
+            namespace_key: "default".into(),
+            flag_key: "flag1".into(),
+            ..Default::default()
+        })
+        .await
+        .expect("evaluate");
+
+    assert_eq!(evaluation.reason, Reason::Unspecified);
+}
+
+#[tokio::test]
+async fn unknown_values_round_trip() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            r#"{"id": "c1", "operator": "matches", "property": "version",
+                "type": "SEMVER_COMPARISON_TYPE", "value": "^1.2", "description": "",
+                "createdAt": "2023-01-01T00:00:00Z", "updatedAt": "2023-01-01T00:00:00Z"}"#,
+        )
+    })
+    .await;
+
+    let constraint = client(&server, AuthScheme::None)
+        .api()
+        .constraints()
+        .create(&ConstraintCreateRequest {
+            segment_key: "segment1".into(),
+            operator: Operator::Unknown("matches".into()),
+            property: "version".into(),
+            comparison_type: ComparisonType::Unknown("SEMVER_COMPARISON_TYPE".into()),
+            value: "^1.2".into(),
+            ..Default::default()
+        })
+        .await
+        .expect("create");
+
+    let requests = server.requests();
+    let body = requests[0].json();
+    assert_eq!(body["operator"], "matches");
+    assert_eq!(body["type"], "SEMVER_COMPARISON_TYPE");
+    assert_eq!(constraint.operator, Operator::Unknown("matches".into()));
+    assert_eq!(
+        constraint.comparison_type,
+        ComparisonType::Unknown("SEMVER_COMPARISON_TYPE".into())
+    );
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use common::{config, StubResponse, StubServer};
+use flipt::api::flag::FlagGetRequest;
+use flipt::error::{Error, FieldViolation};
+use flipt::{AuthScheme, FliptClient};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+async fn get_flag(response: StubResponse) -> Error {
+    let server = StubServer::start(move |_| response.clone()).await;
+    let config = config(&server, AuthScheme::None).set_request_timeout(Duration::from_millis(200));
+    let client = FliptClient::new(config).expect("build client");
+
+    client
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            key: "flag-a".into(),
+            ..Default::default()
+        })
+        .await
+        .expect_err("get flag")
+}
+
+#[tokio::test]
+async fn not_found() {
+    let err = get_flag(StubResponse::json(
+        404,
+        r#"{"code": 5, "message": "flag \"default/flag-a\" not found", "details": []}"#,
+    ))
+    .await;
+
+    let Error::NotFound(resp) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(resp.status, StatusCode::NOT_FOUND);
+    assert_eq!(resp.message(), r#"flag "default/flag-a" not found"#);
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+#[tokio::test]
+async fn unauthenticated() {
+    let err = get_flag(
+        StubResponse::json(
+            401,
+            r#"{"code": 16, "message": "request was not authenticated", "details": []}"#,
+        )
+        .header("X-Request-Id", "abc"),
+    )
+    .await;
+
+    let Error::Unauthenticated(resp) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(resp.headers["x-request-id"], "abc");
+}
+
+#[tokio::test]
+async fn invalid_argument_with_field_violations() {
+    let err = get_flag(StubResponse::json(
+        400,
+        r#"{
+            "code": 3,
+            "message": "invalid field key",
+            "details": [{
+                "@type": "type.googleapis.com/google.rpc.BadRequest",
+                "fieldViolations": [{"field": "key", "description": "must not be empty"}]
+            }]
+        }"#,
+    ))
+    .await;
+
+    let Error::InvalidArgument(resp) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(
+        resp.field_violations(),
+        vec![FieldViolation {
+            field: "key".into(),
+            description: "must not be empty".into(),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn classified_by_status_without_known_code() {
+    let err = get_flag(StubResponse::json(
+        403,
+        r#"{"code": 2, "message": "forbidden", "details": []}"#,
+    ))
+    .await;
+
+    assert!(matches!(err, Error::PermissionDenied(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn decode_error_keeps_body() {
+    let err = get_flag(StubResponse::json(200, r#"{"key": 1}"#)).await;
+
+    let Error::Decode(decode) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(decode.status, StatusCode::OK);
+    assert_eq!(decode.body, r#"{"key": 1}"#);
+}
+
+#[tokio::test]
+async fn client_timeout() {
+    let err = get_flag(StubResponse::json(200, "{}").delay(Duration::from_secs(1))).await;
+
+    assert!(matches!(err, Error::Timeout(None)), "{err:?}");
+}
+
+#[tokio::test]
+async fn html_error_page_from_proxy() {
+    let body = "<html><body><h1>502 Bad Gateway</h1></body></html>";
+    let err = get_flag(StubResponse::new(502, body).header("Content-Type", "text/html")).await;
+
+    let Error::Unavailable(resp) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(resp.status, StatusCode::BAD_GATEWAY);
+    assert_eq!(resp.body, body);
+    assert!(resp.error.is_none());
+    assert!(err.to_string().contains("502 Bad Gateway"));
+}
+
+#[tokio::test]
+async fn plain_text_error_from_proxy() {
+    let err = get_flag(StubResponse::new(
+        429,
+        "upstream connect error or disconnect/reset before headers",
+    ))
+    .await;
+
+    let Error::Upstream(resp) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(resp.status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        resp.message(),
+        "upstream connect error or disconnect/reset before headers"
+    );
+}
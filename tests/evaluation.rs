@@ -0,0 +1,159 @@
+mod common;
+
+use common::{client, StubResponse, StubServer};
+use flipt::evaluation::{
+    BatchEvaluateRequest, ErrorEvaluationReason, EvaluateRequest, EvaluationResult,
+};
+use flipt::AuthScheme;
+
+const BATCH: &str = r#"{
+    "requestId": "batch-1",
+    "requestDurationMillis": 2.0,
+    "responses": [
+        {
+            "type": "BOOLEAN_EVALUATION_RESPONSE_TYPE",
+            "booleanResponse": {
+                "enabled": true, "reason": "MATCH_EVALUATION_REASON", "requestId": "batch-1",
+                "requestDurationMillis": 1.0, "timestamp": "2023-01-01T00:00:00Z",
+                "flagKey": "flag-a"
+            }
+        },
+        {
+            "type": "VARIANT_EVALUATION_RESPONSE_TYPE",
+            "variantResponse": {
+                "match": true, "segmentKeys": ["segment-a"], "reason": "MATCH_EVALUATION_REASON",
+                "variantKey": "treatment", "variantAttachment": "", "requestId": "batch-1",
+                "requestDurationMillis": 1.0, "timestamp": "2023-01-01T00:00:00Z",
+                "flagKey": "flag-b"
+            }
+        },
+        {
+            "type": "ERROR_EVALUATION_RESPONSE_TYPE",
+            "errorResponse": {
+                "flagKey": "flag-c", "namespaceKey": "default",
+                "reason": "NOT_FOUND_ERROR_EVALUATION_REASON"
+            }
+        },
+        {"type": "STRING_EVALUATION_RESPONSE_TYPE"}
+    ]
+}"#;
+
+fn request(flag_key: &str) -> EvaluateRequest {
+    EvaluateRequest {
+        namespace_key: "default".into(),
+        flag_key: flag_key.into(),
+        entity_id: "entity".into(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn request_id_and_reference() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            r#"{"enabled": true, "reason": "MATCH_EVALUATION_REASON", "requestId": "req-1",
+                "requestDurationMillis": 1.0, "timestamp": "2023-01-01T00:00:00Z",
+                "flagKey": "flag-a"}"#,
+        )
+    })
+    .await;
+    let client = client(&server, AuthScheme::None);
+
+    client
+        .evaluation()
+        .boolean(&EvaluateRequest {
+            request_id: "req-1".into(),
+            reference: "v1.2.0".into(),
+            ..request("flag-a")
+        })
+        .await
+        .expect("evaluate");
+    client
+        .evaluation()
+        .boolean(&request("flag-a"))
+        .await
+        .expect("evaluate");
+
+    let requests = server.requests();
+    let body = requests[0].json();
+    assert_eq!(body["requestId"], "req-1");
+    assert_eq!(body["reference"], "v1.2.0");
+    let body = requests[1].json();
+    assert_eq!(body.get("requestId"), None);
+    assert_eq!(body.get("reference"), None);
+}
+
+#[tokio::test]
+async fn batch_results() {
+    let server = StubServer::start(|_| StubResponse::json(200, BATCH)).await;
+
+    let batch = client(&server, AuthScheme::None)
+        .evaluation()
+        .batch(&BatchEvaluateRequest {
+            requests: vec![
+                request("flag-a"),
+                request("flag-b"),
+                request("flag-c"),
+                request("flag-d"),
+            ],
+            request_id: "batch-1".into(),
+            reference: "main".into(),
+        })
+        .await
+        .expect("evaluate");
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["requestId"], "batch-1");
+    assert_eq!(body["reference"], "main");
+
+    let mut results = batch.responses.into_iter();
+    match results.next() {
+        Some(EvaluationResult::Boolean(b)) => assert!(b.enabled),
+        other => panic!("unexpected result: {other:?}"),
+    }
+    match results.next() {
+        Some(EvaluationResult::Variant(v)) => assert_eq!(v.variant_key, "treatment"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+    match results.next() {
+        Some(EvaluationResult::Error(e)) => {
+            assert_eq!(e.flag_key, "flag-c");
+            assert_eq!(e.reason, ErrorEvaluationReason::NotFound);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+    assert_eq!(
+        results.next(),
+        Some(EvaluationResult::Unknown(
+            "STRING_EVALUATION_RESPONSE_TYPE".into()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn batch_response_missing_its_evaluation() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            r#"{"requestId": "batch-1", "requestDurationMillis": 1.0,
+                "responses": [{"type": "BOOLEAN_EVALUATION_RESPONSE_TYPE"}]}"#,
+        )
+    })
+    .await;
+
+    let err = client(&server, AuthScheme::None)
+        .evaluation()
+        .batch(&BatchEvaluateRequest {
+            requests: vec![request("flag-a")],
+            ..Default::default()
+        })
+        .await
+        .expect_err("decode error");
+
+    assert!(
+        err.to_string()
+            .contains("BOOLEAN_EVALUATION_RESPONSE_TYPE response without booleanResponse"),
+        "{err}"
+    );
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use common::{client, StubResponse, StubServer};
+use flipt::api::flag::{FlagCreateRequest, FlagGetRequest, FlagUpdateRequest};
+use flipt::evaluation::EvaluateRequest;
+use flipt::AuthScheme;
+use serde_json::json;
+
+const FLAG_WITH_METADATA: &str = r#"{
+    "namespaceKey": "default",
+    "key": "flag-a",
+    "name": "Flag A",
+    "description": "",
+    "enabled": true,
+    "type": "VARIANT_FLAG_TYPE",
+    "createdAt": "2023-01-01T00:00:00Z",
+    "updatedAt": "2023-01-01T00:00:00Z",
+    "variants": [],
+    "metadata": {"team": "checkout", "ticket": 42},
+    "defaultVariant": {
+        "id": "variant-1",
+        "key": "control",
+        "name": "Control",
+        "description": "",
+        "attachment": "",
+        "createdAt": "2023-01-01T00:00:00Z",
+        "updatedAt": "2023-01-01T00:00:00Z"
+    }
+}"#;
+
+fn metadata() -> serde_json::Map<String, serde_json::Value> {
+    json!({"team": "checkout", "ticket": 42})
+        .as_object()
+        .unwrap()
+        .clone()
+}
+
+#[tokio::test]
+async fn metadata_and_default_variant_are_read() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG_WITH_METADATA)).await;
+
+    let flag = client(&server, AuthScheme::None)
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            namespace_key: None,
+            key: "flag-a".into(),
+        })
+        .await
+        .expect("get flag");
+
+    assert_eq!(flag.metadata, metadata());
+    assert_eq!(
+        flag.default_variant.expect("default variant").key,
+        "control"
+    );
+}
+
+#[tokio::test]
+async fn flags_without_them() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            r#"{"namespaceKey": "default", "key": "flag-a", "name": "Flag A",
+                "description": "", "enabled": true, "createdAt": "2023-01-01T00:00:00Z",
+                "updatedAt": "2023-01-01T00:00:00Z", "variants": []}"#,
+        )
+    })
+    .await;
+
+    let flag = client(&server, AuthScheme::None)
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            namespace_key: None,
+            key: "flag-a".into(),
+        })
+        .await
+        .expect("get flag");
+
+    assert!(flag.metadata.is_empty());
+    assert_eq!(flag.default_variant, None);
+}
+
+#[tokio::test]
+async fn create_sends_metadata() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG_WITH_METADATA)).await;
+    let client = client(&server, AuthScheme::None);
+
+    client
+        .api()
+        .flags()
+        .create(&FlagCreateRequest {
+            key: "flag-a".into(),
+            name: "Flag A".into(),
+            metadata: metadata(),
+            ..Default::default()
+        })
+        .await
+        .expect("create flag");
+    client
+        .api()
+        .flags()
+        .create(&FlagCreateRequest {
+            key: "flag-b".into(),
+            name: "Flag B".into(),
+            ..Default::default()
+        })
+        .await
+        .expect("create flag");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].json()["metadata"], json!(metadata()));
+    assert_eq!(requests[1].json().get("metadata"), None);
+}
+
+#[tokio::test]
+async fn update_sends_default_variant_and_metadata() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG_WITH_METADATA)).await;
+
+    client(&server, AuthScheme::None)
+        .api()
+        .flags()
+        .update(&FlagUpdateRequest {
+            key: "flag-a".into(),
+            name: "Flag A".into(),
+            enabled: true,
+            metadata: Some(metadata()),
+            default_variant_id: Some("variant-1".into()),
+            ..Default::default()
+        })
+        .await
+        .expect("update flag");
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["defaultVariantId"], "variant-1");
+    assert_eq!(body["metadata"], json!(metadata()));
+}
+
+#[tokio::test]
+async fn update_clears_or_keeps_metadata() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG_WITH_METADATA)).await;
+    let flags = client(&server, AuthScheme::None).api().flags();
+
+    for metadata in [Some(serde_json::Map::new()), None] {
+        flags
+            .update(&FlagUpdateRequest {
+                key: "flag-a".into(),
+                name: "Flag A".into(),
+                metadata,
+                ..Default::default()
+            })
+            .await
+            .expect("update flag");
+    }
+
+    let requests = server.requests();
+    assert_eq!(requests[0].json()["metadata"], json!({}));
+    assert_eq!(requests[1].json().get("metadata"), None);
+}
+
+#[tokio::test]
+async fn evaluation_served_default_variant() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            r#"{"match": false, "segmentKeys": [], "reason": "DEFAULT_EVALUATION_REASON",
+                "variantKey": "control", "variantAttachment": "", "requestId": "r",
+                "requestDurationMillis": 1.0, "timestamp": "2023-01-01T00:00:00Z",
+                "flagKey": "flag-a"}"#,
+        )
+    })
+    .await;
+
+    let evaluation = client(&server, AuthScheme::None)
+        .evaluation()
+        .variant(&EvaluateRequest {
+            namespace_key: "default".into(),
+            flag_key: "flag-a".into(),
+            ..Default::default()
+        })
+        .await
+        .expect("evaluate");
+
+    assert!(evaluation.is_default_variant());
+    assert_eq!(evaluation.variant_key, "control");
+}
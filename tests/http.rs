@@ -0,0 +1,109 @@
+mod common;
+
+use common::{client, config, StubResponse, StubServer, FLAG};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use flipt::api::flag::FlagGetRequest;
+use flipt::{AuthScheme, Config, FliptClient, HttpVersion};
+use std::io::Write;
+use std::time::Duration;
+
+async fn get_flag(config: Config) -> flipt::error::Result<()> {
+    FliptClient::new(config)
+        .expect("build client")
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            namespace_key: None,
+            key: "flag-a".into(),
+        })
+        .await
+        .map(|_| ())
+}
+
+fn gzip(body: &str) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body.as_bytes()).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[tokio::test]
+async fn accepts_compressed_responses() {
+    let server = StubServer::start(|_| {
+        StubResponse::bytes(200, gzip(FLAG))
+            .header("Content-Type", "application/json")
+            .header("Content-Encoding", "gzip")
+    })
+    .await;
+
+    let flag = client(&server, AuthScheme::None)
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            namespace_key: None,
+            key: "flag-a".into(),
+        })
+        .await
+        .expect("get flag");
+    assert_eq!(flag.key, "flag-a");
+
+    let request = server.requests().pop().expect("request");
+    let accept_encoding = request.header("accept-encoding").expect("accept-encoding");
+    assert!(accept_encoding.contains("gzip"), "{accept_encoding}");
+    assert!(accept_encoding.contains("br"), "{accept_encoding}");
+}
+
+#[tokio::test]
+async fn compression_can_be_disabled() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+
+    get_flag(
+        config(&server, AuthScheme::None)
+            .set_gzip(false)
+            .set_brotli(false),
+    )
+    .await
+    .expect("get flag");
+
+    let request = server.requests().pop().expect("request");
+    assert_eq!(request.header("accept-encoding"), None);
+}
+
+#[tokio::test]
+async fn pool_settings() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+
+    get_flag(
+        config(&server, AuthScheme::None)
+            .set_http_version(HttpVersion::Http1Only)
+            .set_pool_idle_timeout(Duration::from_secs(30))
+            .set_pool_max_idle_per_host(4)
+            .set_tcp_keepalive(Duration::from_secs(60)),
+    )
+    .await
+    .expect("get flag");
+
+    let request = server.requests().pop().expect("request");
+    assert_eq!(request.path, "/api/v1/namespaces/default/flags/flag-a");
+}
+
+#[tokio::test]
+async fn http2_prior_knowledge_skips_negotiation() {
+    // The stub server only speaks HTTP/1.1, so a client that starts straight
+    // away with HTTP/2 cannot talk to it.
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+
+    let result = get_flag(
+        config(&server, AuthScheme::None).set_http_version(HttpVersion::Http2PriorKnowledge),
+    )
+    .await;
+
+    assert!(result.is_err());
+    // The HTTP/2 connection preface, `PRI * HTTP/2.0`, reads as a request
+    // line.
+    let request = server.requests().pop().expect("preface");
+    assert_eq!(
+        (request.method.as_str(), request.path.as_str()),
+        ("PRI", "*")
+    );
+}
@@ -1,8 +1,16 @@
+#![allow(
+    clippy::bool_assert_comparison,
+    clippy::len_zero,
+    clippy::let_underscore_future,
+    clippy::needless_borrow,
+    clippy::needless_update,
+    clippy::unnecessary_get_then_check
+)]
+
 use flipt::auth::{token::TokenCreateRequest, token::TokenListRequest, AuthClient};
 use flipt::evaluation::{
     EvaluateRequest as V2EvaluateRequest, EvaluationClient, Reason as V2Reason,
 };
-use flipt::Config;
 use flipt::{
     api::{
         constraint::{
@@ -10,11 +18,11 @@ use flipt::{
         },
         distribution::DistributionCreateRequest,
         evaluation::{EvaluateRequest, Reason},
-        flag::{FlagCreateRequest, FlagDeleteRequest, FlagType},
+        flag::{FlagCreateRequest, FlagDeleteRequest, FlagType, FlagUpdateRequest},
         namespace::{NamespaceCreateRequest, NamespaceDeleteRequest},
         rollout::{
-            Rollout, RolloutCreateRequest, RolloutDeleteRequest, RolloutSegment, RolloutThreshold,
-            RolloutType,
+            Rollout, RolloutCreateRequest, RolloutDeleteRequest, RolloutListRequest,
+            RolloutOrderRequest, RolloutSegment, RolloutThreshold, RolloutType,
         },
         rule::{Rule, RuleCreateRequest, RuleDeleteRequest},
         segment::{Match, SegmentCreateRequest, SegmentDeleteRequest},
@@ -23,6 +31,7 @@ use flipt::{
     },
     meta::MetaClient,
 };
+use flipt::{Config, FliptClient};
 
 #[tokio::test]
 #[cfg_attr(not(feature = "flipt_integration"), ignore)]
@@ -53,6 +62,7 @@ async fn integration_api() {
 
     create_flag(&client, FLAG_KEY, FlagType::Variant).await;
     let variant = create_variant(&client, FLAG_KEY, VARIANT_KEY).await;
+    set_default_variant(&client, FLAG_KEY, &variant).await;
     create_segment(&client, SEGMENT_KEY).await;
     let constraint = create_constraint(&client, SEGMENT_KEY).await;
     let rule = create_rule(&client, FLAG_KEY, SEGMENT_KEY).await;
@@ -68,6 +78,13 @@ async fn integration_api() {
     let evaluate_client = EvaluationClient::new(&client);
     boolean_evaluate(&evaluate_client, BOOLEAN_FLAG_KEY).await;
     variant_evaluate(&evaluate_client, FLAG_KEY).await;
+    order_rollouts(
+        &client,
+        BOOLEAN_FLAG_KEY,
+        &threshold_rollout.id,
+        &segment_rollout.id,
+    )
+    .await;
 
     let _ = client
         .flags()
@@ -196,7 +213,7 @@ async fn integration_api() {
                 key: key.into(),
                 name: key.into(),
                 enabled: true,
-                r#type: Some(flag_type),
+                r#type: Some(flag_type.clone()),
                 ..Default::default()
             })
             .await
@@ -228,6 +245,26 @@ async fn integration_api() {
         variant
     }
 
+    async fn set_default_variant(client: &ApiClient, flag_key: &str, variant: &Variant) {
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("team".into(), "checkout".into());
+        let flag = client
+            .flags()
+            .update(&FlagUpdateRequest {
+                key: flag_key.into(),
+                name: flag_key.into(),
+                enabled: true,
+                metadata: Some(metadata.clone()),
+                default_variant_id: Some(variant.id.clone()),
+                ..Default::default()
+            })
+            .await
+            .expect("update flag");
+
+        assert_eq!(flag.metadata, metadata);
+        assert_eq!(flag.default_variant.as_ref(), Some(variant));
+    }
+
     async fn create_segment(client: &ApiClient, key: &str) {
         let segment = client
             .segments()
@@ -332,7 +369,7 @@ async fn integration_api() {
         assert_eq!(eval.reason, Reason::Match);
     }
 
-    async fn boolean_evaluate(client: &EvaluationClient<'_>, flag_key: &str) {
+    async fn boolean_evaluate(client: &EvaluationClient, flag_key: &str) {
         let boolean_evaluation = client
             .boolean(&V2EvaluateRequest {
                 namespace_key: String::from("default"),
@@ -348,7 +385,7 @@ async fn integration_api() {
         assert_eq!(boolean_evaluation.flag_key, flag_key);
     }
 
-    async fn variant_evaluate(client: &EvaluationClient<'_>, flag_key: &str) {
+    async fn variant_evaluate(client: &EvaluationClient, flag_key: &str) {
         let variant_evaluation = client
             .variant(&V2EvaluateRequest {
                 namespace_key: String::from("default"),
@@ -358,11 +395,14 @@ async fn integration_api() {
                     String::from("name"),
                     String::from("brett"),
                 )]),
+                request_id: String::from("integration-variant"),
                 ..Default::default()
             })
             .await
             .expect("variant evaluation");
 
+        assert_eq!(variant_evaluation.request_id, "integration-variant");
+
         assert_eq!(variant_evaluation.is_match, true);
         assert_eq!(variant_evaluation.reason, V2Reason::Match);
         assert_eq!(variant_evaluation.segment_keys[0], "segment-a");
@@ -371,6 +411,43 @@ async fn integration_api() {
         assert_eq!(variant_evaluation.flag_key, flag_key);
     }
 
+    async fn list_rollout_ids(client: &ApiClient, flag_key: &str) -> Vec<(String, u32)> {
+        client
+            .rollouts()
+            .list_all(&RolloutListRequest {
+                flag_key: flag_key.into(),
+                limit: 1,
+                ..Default::default()
+            })
+            .await
+            .expect("list rollouts")
+            .into_iter()
+            .map(|r| (r.id, r.rank))
+            .collect()
+    }
+
+    async fn order_rollouts(client: &ApiClient, flag_key: &str, first: &str, second: &str) {
+        assert_eq!(
+            list_rollout_ids(client, flag_key).await,
+            [(first.to_string(), 1), (second.to_string(), 2)]
+        );
+
+        client
+            .rollouts()
+            .order(&RolloutOrderRequest {
+                flag_key: flag_key.into(),
+                namespace_key: None,
+                rollout_ids: vec![second.into(), first.into()],
+            })
+            .await
+            .expect("order rollouts");
+
+        assert_eq!(
+            list_rollout_ids(client, flag_key).await,
+            [(second.to_string(), 1), (first.to_string(), 2)]
+        );
+    }
+
     async fn delete_rollout(client: &ApiClient, flag_key: &str, id: &str) {
         let _ = client
             .rollouts()
@@ -483,3 +560,23 @@ async fn integration_meta() {
     let info = client.info().get().await.expect("info");
     assert_ne!(info.is_empty(), true);
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "flipt_integration"), ignore)]
+async fn integration_client() {
+    let config = Config::new_from_env().expect("config");
+    let client = FliptClient::new(config).expect("build client");
+
+    let info = client.meta().info().get().await.expect("info");
+    assert!(!info.is_empty());
+
+    let me = client.auth().me().await.expect("me");
+    assert_ne!(me.id, "");
+
+    client
+        .api()
+        .namespaces()
+        .list(&Default::default())
+        .await
+        .expect("list namespaces");
+}
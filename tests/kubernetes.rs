@@ -0,0 +1,162 @@
+mod common;
+
+use chrono::{TimeDelta, Utc};
+use common::{client, StubRequest, StubResponse, StubServer};
+use flipt::error::Error;
+use flipt::AuthScheme;
+use std::path::PathBuf;
+
+const INFO: &str = r#"{"build": {"version": "v1.0.0"}}"#;
+const EXCHANGE: &str = "/auth/v1/method/kubernetes/serviceaccount";
+
+/// Answers exchanges with `client-<service account token>`, valid for `ttl`
+/// or without an expiry. Rejects `client-revoked`.
+async fn server(ttl: Option<TimeDelta>) -> StubServer {
+    StubServer::start(move |req| {
+        if req.path != EXCHANGE {
+            return match req.header("authorization") {
+                Some("Bearer client-revoked") => {
+                    StubResponse::json(401, r#"{"code": 16, "message": "revoked"}"#)
+                }
+                _ => StubResponse::json(200, INFO),
+            };
+        }
+        let token = req.json()["serviceAccountToken"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let expires_at = ttl
+            .map(|ttl| format!(r#""expiresAt": "{}","#, (Utc::now() + ttl).to_rfc3339()))
+            .unwrap_or_default();
+        StubResponse::json(
+            200,
+            &format!(
+                r#"{{"clientToken": "client-{token}", "authentication": {{
+                    "id": "auth-a", "method": "METHOD_KUBERNETES", "metadata": {{}},
+                    {expires_at}
+                    "createdAt": "2023-01-01T00:00:00Z", "updatedAt": "2023-01-01T00:00:00Z"
+                }}}}"#
+            ),
+        )
+    })
+    .await
+}
+
+fn token_file(name: &str, token: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("flipt-k8s-{}-{name}", std::process::id()));
+    std::fs::write(&path, token).unwrap();
+    path
+}
+
+fn summary(requests: &[StubRequest]) -> Vec<(String, Option<String>)> {
+    requests
+        .iter()
+        .map(|r| match r.path.as_str() {
+            EXCHANGE => (
+                format!(
+                    "exchange {}",
+                    r.json()["serviceAccountToken"].as_str().unwrap()
+                ),
+                r.header("authorization").map(Into::into),
+            ),
+            path => (path.to_string(), r.header("authorization").map(Into::into)),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn exchanges_service_account_token() {
+    let server = server(Some(TimeDelta::hours(1))).await;
+    let client = client(
+        &server,
+        AuthScheme::Kubernetes(token_file("exchange", "sa-1\n")),
+    );
+
+    client.meta().info().get().await.expect("get info");
+    client.meta().info().get().await.expect("get info");
+
+    let bearer = Some("Bearer client-sa-1".to_string());
+    assert_eq!(
+        summary(&server.requests()),
+        vec![
+            ("exchange sa-1".to_string(), None),
+            ("/meta/info".to_string(), bearer.clone()),
+            ("/meta/info".to_string(), bearer),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn refreshes_before_expiry_with_rotated_token() {
+    // Expires within the refresh margin, so every request exchanges again.
+    let server = server(Some(TimeDelta::seconds(10))).await;
+    let path = token_file("rotate", "sa-1");
+    let client = client(&server, AuthScheme::Kubernetes(path.clone()));
+
+    client.meta().info().get().await.expect("get info");
+    std::fs::write(&path, "sa-2").unwrap();
+    client.meta().info().get().await.expect("get info");
+
+    assert_eq!(
+        summary(&server.requests()),
+        vec![
+            ("exchange sa-1".to_string(), None),
+            ("/meta/info".to_string(), Some("Bearer client-sa-1".into())),
+            ("exchange sa-2".to_string(), None),
+            ("/meta/info".to_string(), Some("Bearer client-sa-2".into())),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn caches_token_without_expiry_until_rejected() {
+    let server = server(None).await;
+    let path = token_file("no-expiry", "revoked");
+    let client = client(&server, AuthScheme::Kubernetes(path.clone()));
+
+    let err = client.meta().info().get().await.expect_err("revoked");
+    assert!(matches!(err, Error::Unauthenticated(_)), "{err:?}");
+    std::fs::write(&path, "sa-2").unwrap();
+    client.meta().info().get().await.expect("get info");
+    client.meta().info().get().await.expect("get info");
+
+    let bearer = Some("Bearer client-sa-2".to_string());
+    assert_eq!(
+        summary(&server.requests()),
+        vec![
+            ("exchange revoked".to_string(), None),
+            (
+                "/meta/info".to_string(),
+                Some("Bearer client-revoked".into())
+            ),
+            ("exchange sa-2".to_string(), None),
+            ("/meta/info".to_string(), bearer.clone()),
+            ("/meta/info".to_string(), bearer),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn exchange_errors() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(401, r#"{"code": 16, "message": "invalid token"}"#)
+    })
+    .await;
+    let rejected = client(
+        &server,
+        AuthScheme::Kubernetes(token_file("rejected", "sa-1")),
+    );
+
+    let err = rejected.meta().info().get().await.expect_err("rejected");
+    assert!(matches!(err, Error::Unauthenticated(_)), "{err:?}");
+    assert_eq!(server.requests().len(), 1);
+
+    let missing = client(&server, AuthScheme::Kubernetes("/does/not/exist".into()));
+    let err = missing
+        .meta()
+        .info()
+        .get()
+        .await
+        .expect_err("missing token");
+    assert!(matches!(err, Error::Config(_)), "{err:?}");
+}
@@ -0,0 +1,265 @@
+mod common;
+
+use common::{client, StubRequest, StubResponse, StubServer};
+use flipt::auth::{Login, Method};
+use flipt::error::{Error, Result};
+use flipt::AuthScheme;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use url::Url;
+
+const AUTHENTICATION: &str = r#"{
+    "id": "auth-a",
+    "method": "METHOD_OIDC",
+    "metadata": {"io.flipt.auth.oidc.email": "dev@example.com"},
+    "expiresAt": "2030-01-01T00:00:00Z",
+    "createdAt": "2023-01-01T00:00:00Z",
+    "updatedAt": "2023-01-01T00:00:00Z"
+}"#;
+
+fn query(req: &StubRequest, key: &str) -> Option<String> {
+    let url = Url::parse(&format!("http://stub{}", req.path)).unwrap();
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.into_owned())
+}
+
+/// A free loopback address for the redirect listener.
+fn redirect_addr() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+/// An identity provider redirecting back to `redirect_uri` with `outcome`
+/// as the query, where `{state}` is replaced with the state it was given.
+async fn provider(outcome: &'static str) -> StubServer {
+    StubServer::start(move |req| {
+        let location = format!(
+            "{}?{}",
+            query(req, "redirect_uri").unwrap(),
+            outcome.replace("{state}", &query(req, "state").unwrap())
+        );
+        StubResponse::new(302, "").header("Location", &location)
+    })
+    .await
+}
+
+/// Flipt, sending users to `provider` and accepting the code `abc`.
+async fn flipt(provider: &StubServer, redirect: SocketAddr) -> StubServer {
+    let authorize = provider.endpoint().join("authorize").unwrap();
+    StubServer::start(move |req| {
+        let path = req.path.split('?').next().unwrap();
+        if path.ends_with("/authorize") {
+            let mut url = authorize.clone();
+            url.query_pairs_mut()
+                .append_pair("state", &query(req, "state").unwrap())
+                .append_pair("redirect_uri", &format!("http://{redirect}/callback"));
+            return StubResponse::json(200, &format!(r#"{{"authorizeUrl": "{url}"}}"#));
+        }
+        match query(req, "code").as_deref() {
+            Some("abc") => StubResponse::json(
+                200,
+                &format!(
+                    r#"{{"clientToken": "client-token", "authentication": {AUTHENTICATION}}}"#
+                ),
+            ),
+            _ => StubResponse::json(401, r#"{"code": 16, "message": "invalid code"}"#),
+        }
+    })
+    .await
+}
+
+/// Follows the authorize URL like a browser would.
+fn browse(url: &str) {
+    let url = url.to_string();
+    tokio::spawn(async move {
+        let _ = reqwest::get(url).await;
+    });
+}
+
+async fn oidc_login(outcome: &'static str) -> (StubServer, Result<Login>) {
+    let provider = provider(outcome).await;
+    let redirect = redirect_addr();
+    let flipt = flipt(&provider, redirect).await;
+
+    let login = client(&flipt, AuthScheme::None)
+        .auth()
+        .oidc()
+        .with_login_timeout(Duration::from_secs(1))
+        .login("google", redirect, browse)
+        .await;
+    (flipt, login)
+}
+
+#[tokio::test]
+async fn oidc() {
+    let (flipt, login) = oidc_login("code=abc&state={state}").await;
+
+    let login = login.expect("login");
+    assert_eq!(login.client_token, "client-token");
+    assert_eq!(login.authentication.method, Method::Oidc);
+
+    let requests = flipt.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0]
+        .path
+        .starts_with("/auth/v1/method/oidc/google/authorize?"));
+    assert!(requests[1]
+        .path
+        .starts_with("/auth/v1/method/oidc/google/callback?"));
+    let state = query(&requests[0], "state").unwrap();
+    assert_eq!(state.len(), 32);
+    assert_eq!(query(&requests[1], "state"), Some(state.clone()));
+    assert_eq!(
+        requests[1].header("cookie"),
+        Some(format!("flipt_client_state={state}").as_str())
+    );
+}
+
+#[tokio::test]
+async fn github() {
+    let provider = provider("code=abc&state={state}").await;
+    let redirect = redirect_addr();
+    let flipt = flipt(&provider, redirect).await;
+
+    let login = client(&flipt, AuthScheme::None)
+        .auth()
+        .github()
+        .login(redirect, browse)
+        .await
+        .expect("login");
+
+    assert_eq!(login.client_token, "client-token");
+    let paths: Vec<_> = flipt
+        .requests()
+        .into_iter()
+        .map(|r| r.path.split('?').next().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            "/auth/v1/method/github/authorize",
+            "/auth/v1/method/github/callback"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn denied_by_provider() {
+    let (flipt, login) =
+        oidc_login("error=access_denied&error_description=user+declined&state={state}").await;
+
+    let err = login.expect_err("denied");
+    assert!(matches!(err, Error::Login(_)), "{err:?}");
+    assert_eq!(err.to_string(), "login failed: access_denied user declined");
+    assert_eq!(flipt.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn ignores_mismatched_state() {
+    let (flipt, login) = oidc_login("code=abc&state=forged").await;
+
+    let err = login.expect_err("forged state");
+    assert_eq!(
+        err.to_string(),
+        "login failed: timed out waiting for the redirect"
+    );
+    assert_eq!(flipt.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn rejected_code() {
+    let (_, login) = oidc_login("code=wrong&state={state}").await;
+
+    let err = login.expect_err("rejected code");
+    assert!(matches!(err, Error::Unauthenticated(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn ignores_error_with_mismatched_state() {
+    let (flipt, login) = oidc_login("error=access_denied&state=forged").await;
+
+    let err = login.expect_err("forged state");
+    assert_eq!(
+        err.to_string(),
+        "login failed: timed out waiting for the redirect"
+    );
+    assert_eq!(flipt.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn forged_redirect_does_not_end_login() {
+    let provider = provider("code=abc&state={state}").await;
+    let redirect = redirect_addr();
+    let flipt = flipt(&provider, redirect).await;
+
+    let (forged_tx, forged) = tokio::sync::oneshot::channel();
+    let login = client(&flipt, AuthScheme::None)
+        .auth()
+        .oidc()
+        .with_login_timeout(Duration::from_secs(5))
+        .login("google", redirect, move |url| {
+            let url = url.to_string();
+            tokio::spawn(async move {
+                let forged_url = format!("http://{redirect}/callback?code=evil&state=forged");
+                let status = reqwest::get(forged_url).await.map(|r| r.status().as_u16());
+                let _ = forged_tx.send(status.ok());
+                let _ = reqwest::get(url).await;
+            });
+        })
+        .await
+        .expect("login");
+
+    assert_eq!(login.client_token, "client-token");
+    assert_eq!(forged.await.unwrap(), Some(400));
+}
+
+#[tokio::test]
+async fn idle_connection_does_not_delay_redirect() {
+    let provider = provider("code=abc&state={state}").await;
+    let redirect = redirect_addr();
+    let flipt = flipt(&provider, redirect).await;
+
+    let start = Instant::now();
+    client(&flipt, AuthScheme::None)
+        .auth()
+        .oidc()
+        .login("google", redirect, move |url| {
+            let url = url.to_string();
+            tokio::spawn(async move {
+                // Like a browser preconnect that never sends a request.
+                let _idle = tokio::net::TcpStream::connect(redirect).await;
+                let _ = reqwest::get(url).await;
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            });
+        })
+        .await
+        .expect("login");
+
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn gives_up_after_login_timeout() {
+    let provider = provider("code=abc&state={state}").await;
+    let redirect = redirect_addr();
+    let flipt = flipt(&provider, redirect).await;
+
+    let start = Instant::now();
+    let err = client(&flipt, AuthScheme::None)
+        .auth()
+        .oidc()
+        .with_login_timeout(Duration::from_millis(100))
+        .login("google", redirect, |_| {
+            // A connection that never sends a request, and no browser.
+            tokio::spawn(tokio::net::TcpStream::connect(redirect));
+        })
+        .await
+        .expect_err("timed out");
+
+    assert_eq!(
+        err.to_string(),
+        "login failed: timed out waiting for the redirect"
+    );
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "metrics")]
+
+mod common;
+
+use common::{client, StubResponse, StubServer};
+use flipt::evaluation::{BatchEvaluateRequest, EvaluateRequest};
+use flipt::AuthScheme;
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use std::collections::HashMap;
+use std::future::Future;
+
+const BATCH_EVALUATION: &str = r#"{
+    "requestId": "request-a",
+    "requestDurationMillis": 1.0,
+    "responses": [
+        {
+            "type": "VARIANT_EVALUATION_RESPONSE_TYPE",
+            "variantResponse": {
+                "match": true,
+                "segmentKeys": ["segment-a"],
+                "reason": "MATCH_EVALUATION_REASON",
+                "variantKey": "blue",
+                "variantAttachment": "",
+                "requestId": "request-a",
+                "requestDurationMillis": 1.0,
+                "timestamp": "2023-01-01T00:00:00Z",
+                "flagKey": "flag-a"
+            }
+        },
+        {
+            "type": "BOOLEAN_EVALUATION_RESPONSE_TYPE",
+            "booleanResponse": {
+                "enabled": false,
+                "reason": "DEFAULT_EVALUATION_REASON",
+                "requestId": "request-a",
+                "requestDurationMillis": 1.0,
+                "timestamp": "2023-01-01T00:00:00Z",
+                "flagKey": "flag-b"
+            }
+        }
+    ]
+}"#;
+
+struct Metric {
+    labels: HashMap<String, String>,
+    value: DebugValue,
+}
+
+/// Runs `f` on a single-threaded runtime with a local recorder and returns
+/// everything recorded under `name`.
+fn record<F, Fut>(name: &str, f: F) -> Vec<Metric>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+    metrics::with_local_recorder(&recorder, || runtime.block_on(f()));
+
+    snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .filter(|(key, ..)| key.key().name() == name)
+        .map(|(key, _, _, value)| Metric {
+            labels: key
+                .key()
+                .labels()
+                .map(|l| (l.key().to_string(), l.value().to_string()))
+                .collect(),
+            value,
+        })
+        .collect()
+}
+
+#[test]
+fn request_counter_and_latency() {
+    let requests = record("flipt_client_requests_total", || async {
+        let server = StubServer::start(|_| StubResponse::json(503, "{}")).await;
+        let _ = client(&server, AuthScheme::None).meta().info().get().await;
+    });
+
+    assert_eq!(requests.len(), 1);
+    let labels = &requests[0].labels;
+    assert_eq!(labels["endpoint"], "/meta/info");
+    assert_eq!(labels["method"], "GET");
+    assert_eq!(labels["status"], "503");
+    assert_eq!(labels["outcome"], "unavailable");
+    assert_eq!(requests[0].value, DebugValue::Counter(1));
+
+    let latency = record("flipt_client_request_duration_seconds", || async {
+        let server = StubServer::start(|_| StubResponse::json(200, "{}")).await;
+        let _ = client(&server, AuthScheme::None).meta().info().get().await;
+    });
+    assert_eq!(latency.len(), 1);
+    assert!(matches!(&latency[0].value, DebugValue::Histogram(v) if v.len() == 1));
+}
+
+#[test]
+fn evaluation_counter() {
+    let evaluations = record("flipt_client_evaluations_total", || async {
+        let server = StubServer::start(|_| StubResponse::json(200, BATCH_EVALUATION)).await;
+        let request = |flag_key: &str| EvaluateRequest {
+            namespace_key: "production".into(),
+            flag_key: flag_key.into(),
+            entity_id: "entity".into(),
+            ..Default::default()
+        };
+        client(&server, AuthScheme::None)
+            .evaluation()
+            .batch(&BatchEvaluateRequest {
+                requests: vec![request("flag-a"), request("flag-b")],
+                ..Default::default()
+            })
+            .await
+            .expect("evaluate");
+    });
+
+    let mut labels: Vec<_> = evaluations
+        .iter()
+        .map(|m| {
+            (
+                m.labels["namespace"].as_str(),
+                m.labels["flag_key"].as_str(),
+                m.labels["reason"].as_str(),
+                m.labels["match"].as_str(),
+            )
+        })
+        .collect();
+    labels.sort();
+    assert_eq!(
+        labels,
+        vec![
+            ("production", "flag-a", "match", "true"),
+            ("production", "flag-b", "default", "false"),
+        ]
+    );
+}
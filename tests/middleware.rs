@@ -0,0 +1,147 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{StubResponse, StubServer, FLAG};
+use flipt::api::flag::FlagGetRequest;
+use flipt::error::{Error, Result};
+use flipt::middleware::{Middleware, Next};
+use flipt::retry::RetryPolicy;
+use flipt::{AuthScheme, Config, FliptClient};
+use std::sync::{Arc, Mutex};
+
+struct Tenant(&'static str);
+
+#[async_trait]
+impl Middleware for Tenant {
+    async fn handle(
+        &self,
+        mut request: reqwest::Request,
+        next: Next<'_>,
+    ) -> Result<reqwest::Response> {
+        request
+            .headers_mut()
+            .insert("X-Tenant", self.0.parse().unwrap());
+        next.run(request).await
+    }
+}
+
+/// Records the order requests and responses pass through it.
+struct Recorder {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Middleware for Recorder {
+    async fn handle(&self, request: reqwest::Request, next: Next<'_>) -> Result<reqwest::Response> {
+        let authorized = request.headers().contains_key("authorization");
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("{} request authorized={authorized}", self.name));
+
+        let response = next.run(request).await?;
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("{} response {}", self.name, response.status()));
+        Ok(response)
+    }
+}
+
+struct Reject;
+
+#[async_trait]
+impl Middleware for Reject {
+    async fn handle(&self, _: reqwest::Request, _: Next<'_>) -> Result<reqwest::Response> {
+        Err(Error::Internal("rejected".into()))
+    }
+}
+
+fn flag_request() -> FlagGetRequest {
+    FlagGetRequest {
+        key: "flag-a".into(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn adds_headers_to_every_request() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+    let config = Config::new(server.endpoint(), AuthScheme::BearerToken("secret".into()))
+        .add_middleware(Tenant("payments"));
+    let client = FliptClient::new(config).expect("build client");
+
+    client
+        .api()
+        .flags()
+        .get(&flag_request())
+        .await
+        .expect("get flag");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].header("x-tenant"), Some("payments"));
+    assert_eq!(requests[0].header("authorization"), Some("Bearer secret"));
+}
+
+#[tokio::test]
+async fn runs_in_order_after_authentication() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let config = Config::new(server.endpoint(), AuthScheme::BearerToken("secret".into()))
+        .add_middleware(Recorder {
+            name: "outer",
+            log: log.clone(),
+        })
+        .add_middleware(Recorder {
+            name: "inner",
+            log: log.clone(),
+        });
+    let client = FliptClient::new(config).expect("build client");
+
+    client.meta().info().get().await.expect("info");
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "outer request authorized=true",
+            "inner request authorized=true",
+            "inner response 200 OK",
+            "outer response 200 OK",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn sees_every_retry_attempt() {
+    let server = StubServer::start(|_| StubResponse::json(503, "{}")).await;
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let config = Config::new(server.endpoint(), AuthScheme::None)
+        .set_retry_policy(
+            RetryPolicy::new()
+                .set_max_attempts(2)
+                .set_base_backoff(std::time::Duration::from_millis(1)),
+        )
+        .add_middleware(Recorder {
+            name: "recorder",
+            log: log.clone(),
+        });
+    let client = FliptClient::new(config).expect("build client");
+
+    let result = client.api().flags().get(&flag_request()).await;
+
+    assert!(matches!(result, Err(Error::Unavailable(_))));
+    assert_eq!(log.lock().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn short_circuits_request() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+    let config = Config::new(server.endpoint(), AuthScheme::None).add_middleware(Reject);
+    let client = FliptClient::new(config).expect("build client");
+
+    let result = client.auth().me().await;
+
+    assert!(matches!(result, Err(Error::Internal(_))));
+    assert!(server.requests().is_empty());
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use common::{config, StubResponse, StubServer};
+use flipt::api::flag::FlagGetRequest;
+use flipt::api::rule::RuleListRequest;
+use flipt::api::segment::SegmentCreateRequest;
+use flipt::evaluation::{BatchEvaluateRequest, EvaluateRequest};
+use flipt::retry::RetryPolicy;
+use flipt::{AuthScheme, Config, FliptClient};
+
+async fn server() -> StubServer {
+    StubServer::start(|_| StubResponse::json(500, "{}")).await
+}
+
+/// Request paths without their query strings.
+fn paths(server: &StubServer) -> Vec<String> {
+    server
+        .requests()
+        .into_iter()
+        .map(|r| r.path.split('?').next().unwrap().to_string())
+        .collect()
+}
+
+fn flag_request(namespace_key: Option<&str>) -> FlagGetRequest {
+    FlagGetRequest {
+        namespace_key: namespace_key.map(Into::into),
+        key: "flag-a".into(),
+    }
+}
+
+#[tokio::test]
+async fn configured_default_namespace() {
+    let server = server().await;
+    let client =
+        FliptClient::new(config(&server, AuthScheme::None).set_namespace("payments")).unwrap();
+
+    let _ = client.api().flags().get(&flag_request(None)).await;
+    let _ = client.api().flags().get(&flag_request(Some("other"))).await;
+
+    assert_eq!(
+        paths(&server),
+        vec![
+            "/api/v1/namespaces/payments/flags/flag-a",
+            "/api/v1/namespaces/other/flags/flag-a",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn namespace_scoped_clients() {
+    let server = server().await;
+    let client = FliptClient::new(config(&server, AuthScheme::None)).unwrap();
+
+    let payments = client.namespace("payments");
+    let flags = payments.flags();
+    let _ = flags.get(&flag_request(None)).await;
+    let _ = payments
+        .rules()
+        .list(&RuleListRequest {
+            flag_key: "flag-a".into(),
+            ..Default::default()
+        })
+        .await;
+    let _ = payments
+        .segments()
+        .create(&SegmentCreateRequest {
+            key: "segment-a".into(),
+            ..Default::default()
+        })
+        .await;
+    let _ = client.api().flags().get(&flag_request(None)).await;
+
+    assert_eq!(
+        paths(&server),
+        vec![
+            "/api/v1/namespaces/payments/flags/flag-a",
+            "/api/v1/namespaces/payments/flags/flag-a/rules",
+            "/api/v1/namespaces/payments/segments",
+            "/api/v1/namespaces/default/flags/flag-a",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn namespace_from_env() {
+    let server = server().await;
+    std::env::set_var("FLIPT_ENDPOINT", server.endpoint().as_str());
+    std::env::set_var("FLIPT_NAMESPACE", "payments");
+    let config = Config::new_from_env().expect("config");
+    std::env::remove_var("FLIPT_NAMESPACE");
+
+    let client = FliptClient::new(config.set_retry_policy(RetryPolicy::disabled())).unwrap();
+    let _ = client.api().flags().get(&flag_request(None)).await;
+
+    assert_eq!(
+        paths(&server),
+        vec!["/api/v1/namespaces/payments/flags/flag-a"]
+    );
+}
+
+fn evaluate_request(namespace_key: &str) -> EvaluateRequest {
+    EvaluateRequest {
+        namespace_key: namespace_key.into(),
+        flag_key: "flag-a".into(),
+        ..Default::default()
+    }
+}
+
+/// Namespace keys in the bodies of the evaluation requests.
+fn evaluated_namespaces(server: &StubServer) -> Vec<String> {
+    server
+        .requests()
+        .iter()
+        .flat_map(|r| {
+            let body = r.json();
+            match body["requests"].as_array() {
+                Some(requests) => requests.clone(),
+                None => vec![body],
+            }
+        })
+        .map(|v| v["namespaceKey"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn evaluation_uses_configured_namespace() {
+    let server = server().await;
+    let client =
+        FliptClient::new(config(&server, AuthScheme::None).set_namespace("payments")).unwrap();
+
+    let evaluation = client.evaluation();
+    let _ = evaluation.boolean(&evaluate_request("")).await;
+    let _ = evaluation.variant(&evaluate_request("other")).await;
+    let _ = evaluation
+        .batch(&BatchEvaluateRequest {
+            requests: vec![evaluate_request(""), evaluate_request("other")],
+            ..Default::default()
+        })
+        .await;
+
+    assert_eq!(
+        evaluated_namespaces(&server),
+        vec!["payments", "other", "payments", "other"]
+    );
+}
+
+#[tokio::test]
+async fn namespace_scoped_evaluation() {
+    let server = server().await;
+    let client = FliptClient::new(config(&server, AuthScheme::None)).unwrap();
+
+    let evaluation = client.namespace("payments").evaluation();
+    let _ = evaluation.boolean(&evaluate_request("")).await;
+    let _ = evaluation.variant(&evaluate_request("other")).await;
+
+    assert_eq!(evaluated_namespaces(&server), vec!["payments", "other"]);
+    assert_eq!(
+        paths(&server),
+        vec!["/evaluate/v1/boolean", "/evaluate/v1/variant"]
+    );
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{client, StubResponse, StubServer, FLAG};
+use flipt::api::flag::{FlagClient, FlagGetRequest};
+use flipt::api::{ApiClient, NamespacedClient};
+use flipt::evaluation::EvaluationClient;
+use flipt::{AuthScheme, FliptClient};
+
+fn assert_shareable<T: Send + Sync + Clone + 'static>() {}
+
+#[test]
+fn clients_are_shareable() {
+    assert_shareable::<FliptClient>();
+    assert_shareable::<ApiClient>();
+    assert_shareable::<NamespacedClient>();
+    assert_shareable::<FlagClient>();
+    assert_shareable::<EvaluationClient>();
+}
+
+/// Application state holding a sub-client, as an axum handler would.
+#[derive(Clone)]
+struct State {
+    flags: FlagClient,
+}
+
+#[tokio::test]
+async fn sub_client_moves_into_task() {
+    let server = StubServer::start(|_| StubResponse::json(200, FLAG)).await;
+    let state = State {
+        flags: client(&server, AuthScheme::None).api().flags(),
+    };
+
+    let tasks: Vec<_> = (0..2)
+        .map(|_| {
+            let state = state.clone();
+            tokio::spawn(async move {
+                state
+                    .flags
+                    .get(&FlagGetRequest {
+                        namespace_key: None,
+                        key: "flag-a".into(),
+                    })
+                    .await
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        let flag = task.await.unwrap().expect("get flag");
+        assert_eq!(flag.key, "flag-a");
+    }
+    assert_eq!(server.requests().len(), 2);
+}
@@ -0,0 +1,228 @@
+mod common;
+
+use common::{client, StubRequest, StubResponse, StubServer};
+use flipt::api::namespace::NamespaceListRequest;
+use flipt::api::rollout::RolloutListRequest;
+use flipt::api::segment::SegmentListRequest;
+use flipt::error::Error;
+use flipt::AuthScheme;
+use futures_util::{StreamExt, TryStreamExt};
+
+fn namespace(key: &str) -> String {
+    format!(
+        r#"{{"key": "{key}", "name": "{key}", "description": "", "protected": false,
+            "createdAt": "2023-01-01T00:00:00Z", "updatedAt": "2023-01-01T00:00:00Z"}}"#
+    )
+}
+
+fn namespaces(keys: &[&str], next_page_token: &str) -> StubResponse {
+    let items: Vec<_> = keys.iter().map(|k| namespace(k)).collect();
+    StubResponse::json(
+        200,
+        &format!(
+            r#"{{"namespaces": [{}], "nextPageToken": "{next_page_token}", "totalCount": 5}}"#,
+            items.join(",")
+        ),
+    )
+}
+
+fn query(req: &StubRequest, key: &str) -> Option<String> {
+    let (_, query) = req.path.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|kv| kv.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.to_string())
+}
+
+/// Serves three pages of namespaces, keyed by page token.
+fn pages(req: &StubRequest) -> StubResponse {
+    match query(req, "pageToken").as_deref() {
+        Some("") | None => namespaces(&["a", "b"], "page-2"),
+        Some("page-2") => namespaces(&["c", "d"], "page-3"),
+        Some("page-3") => namespaces(&["e"], ""),
+        Some(_) => StubResponse::json(400, "{}"),
+    }
+}
+
+#[tokio::test]
+async fn list_all_follows_page_tokens() {
+    let server = StubServer::start(pages).await;
+    let client = client(&server, AuthScheme::None);
+
+    let all = client
+        .api()
+        .namespaces()
+        .list_all(&NamespaceListRequest {
+            limit: 2,
+            ..Default::default()
+        })
+        .await
+        .expect("list all");
+
+    let keys: Vec<_> = all.iter().map(|n| n.key.as_str()).collect();
+    assert_eq!(keys, vec!["a", "b", "c", "d", "e"]);
+
+    let requests = server.requests();
+    let tokens: Vec<_> = requests.iter().map(|r| query(r, "pageToken")).collect();
+    assert_eq!(
+        tokens,
+        vec![
+            Some("".into()),
+            Some("page-2".into()),
+            Some("page-3".into())
+        ]
+    );
+    assert!(requests
+        .iter()
+        .all(|r| query(r, "limit").as_deref() == Some("2")));
+}
+
+#[tokio::test]
+async fn stream_fetches_lazily() {
+    let server = StubServer::start(pages).await;
+    let client = client(&server, AuthScheme::None);
+
+    let mut stream = client
+        .api()
+        .namespaces()
+        .stream(&NamespaceListRequest::default())
+        .page_size(2);
+    assert!(server.requests().is_empty());
+
+    stream.next().await.unwrap().unwrap();
+    stream.next().await.unwrap().unwrap();
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(query(&server.requests()[0], "limit").as_deref(), Some("2"));
+
+    stream.next().await.unwrap().unwrap();
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn stream_with_prefetch() {
+    let server = StubServer::start(pages).await;
+    let client = client(&server, AuthScheme::None);
+
+    let stream = client
+        .api()
+        .namespaces()
+        .stream(&NamespaceListRequest::default())
+        .prefetch(2);
+
+    let keys: Vec<_> = stream.map(|n| n.unwrap().key).collect().await;
+    assert_eq!(keys, vec!["a", "b", "c", "d", "e"]);
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn stops_on_empty_page() {
+    let server = StubServer::start(|_| namespaces(&[], "page-2")).await;
+    let client = client(&server, AuthScheme::None);
+
+    let all = client
+        .api()
+        .namespaces()
+        .list_all(&NamespaceListRequest::default())
+        .await
+        .expect("list all");
+
+    assert!(all.is_empty());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn error_ends_stream_after_received_items() {
+    let server = StubServer::start(|req| match query(req, "pageToken").as_deref() {
+        Some("") => namespaces(&["a"], "page-2"),
+        _ => StubResponse::json(404, r#"{"code": 5, "message": "not found"}"#),
+    })
+    .await;
+    let client = client(&server, AuthScheme::None);
+
+    let results: Vec<_> = client
+        .api()
+        .namespaces()
+        .stream(&NamespaceListRequest::default())
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap().key, "a");
+    assert!(matches!(results[1], Err(Error::NotFound(_))));
+}
+
+#[tokio::test]
+async fn segments() {
+    let server = StubServer::start(|_| {
+        StubResponse::json(
+            200,
+            r#"{"segments": [{"namespaceKey": "default", "key": "segment-a",
+                "matchType": "ALL_MATCH_TYPE", "name": "Segment A", "description": "",
+                "createdAt": "2023-01-01T00:00:00Z", "updatedAt": "2023-01-01T00:00:00Z",
+                "constraints": []}], "nextPageToken": "", "totalCount": 1}"#,
+        )
+    })
+    .await;
+    let client = client(&server, AuthScheme::None);
+
+    let segments: Vec<_> = client
+        .api()
+        .segments()
+        .stream(&SegmentListRequest::default())
+        .try_collect()
+        .await
+        .expect("stream segments");
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].key, "segment-a");
+}
+
+fn rollout(id: &str, rank: u32) -> String {
+    format!(
+        r#"{{"id": "{id}", "rank": {rank}, "type": "THRESHOLD_ROLLOUT_TYPE", "description": "",
+            "createdAt": "2023-01-01T00:00:00Z", "updatedAt": "2023-01-01T00:00:00Z",
+            "threshold": {{"percentage": 50.0, "value": true}}}}"#
+    )
+}
+
+#[tokio::test]
+async fn rollouts() {
+    let server = StubServer::start(|req| match query(req, "pageToken").as_deref() {
+        Some("page-2") => StubResponse::json(
+            200,
+            &format!(
+                r#"{{"rules": [{}], "nextPageToken": "", "totalCount": 2}}"#,
+                rollout("r2", 2)
+            ),
+        ),
+        _ => StubResponse::json(
+            200,
+            &format!(
+                r#"{{"rules": [{}], "nextPageToken": "page-2", "totalCount": 2}}"#,
+                rollout("r1", 1)
+            ),
+        ),
+    })
+    .await;
+    let client = client(&server, AuthScheme::None);
+
+    let rollouts = client
+        .api()
+        .rollouts()
+        .list_all(&RolloutListRequest {
+            flag_key: "flag-a".into(),
+            limit: 1,
+            ..Default::default()
+        })
+        .await
+        .expect("list rollouts");
+
+    let ranks: Vec<_> = rollouts.iter().map(|r| (r.id.as_str(), r.rank)).collect();
+    assert_eq!(ranks, [("r1", 1), ("r2", 2)]);
+    let requests = server.requests();
+    assert_eq!(
+        requests[1].path,
+        "/api/v1/namespaces/default/flags/flag-a/rollouts?limit=1&pageToken=page-2"
+    );
+}
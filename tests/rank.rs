@@ -0,0 +1,342 @@
+mod common;
+
+use common::{client, StubRequest, StubResponse, StubServer};
+use flipt::api::rank::Position;
+use flipt::api::rollout::RolloutMoveRequest;
+use flipt::api::rule::{RuleCreateRequest, RuleMoveRequest, RuleSwapRequest};
+use flipt::error::Error;
+use flipt::AuthScheme;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+const RULES: &str = "/api/v1/namespaces/default/flags/flag-a/rules";
+const ROLLOUTS: &str = "/api/v1/namespaces/default/flags/flag-a/rollouts";
+
+fn rule(id: &str, rank: usize) -> String {
+    format!(
+        r#"{{"id": "{id}", "rank": {rank}, "distributions": [], "segmentKey": "segment-a",
+            "flagKey": "flag-a", "createdAt": "2023-01-01T00:00:00Z",
+            "updatedAt": "2023-01-01T00:00:00Z"}}"#
+    )
+}
+
+fn rollout(id: &str, rank: usize) -> String {
+    format!(
+        r#"{{"id": "{id}", "rank": {rank}, "type": "THRESHOLD_ROLLOUT_TYPE", "description": "",
+            "createdAt": "2023-01-01T00:00:00Z", "updatedAt": "2023-01-01T00:00:00Z",
+            "threshold": {{"percentage": 50.0, "value": true}}}}"#
+    )
+}
+
+/// Keeps the order of the rules of a flag like Flipt does. `after` runs
+/// with the method of every request once it is handled, to simulate
+/// concurrent changes.
+fn rules(
+    ids: &[&str],
+    after: impl Fn(&str, &mut Vec<String>) + Send + Sync + 'static,
+) -> (
+    Arc<Mutex<Vec<String>>>,
+    impl Fn(&StubRequest) -> StubResponse,
+) {
+    let order = Arc::new(Mutex::new(
+        ids.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+    ));
+    let state = order.clone();
+    let handler = move |req: &StubRequest| {
+        let mut order = state.lock().unwrap();
+        let response = match (req.method.as_str(), req.path.split('?').next().unwrap()) {
+            ("GET", RULES) => {
+                let rules: Vec<_> = order
+                    .iter()
+                    .enumerate()
+                    .map(|(i, id)| rule(id, i + 1))
+                    .collect();
+                StubResponse::json(
+                    200,
+                    &format!(
+                        r#"{{"rules": [{}], "nextPageToken": "", "totalCount": {}}}"#,
+                        rules.join(","),
+                        rules.len()
+                    ),
+                )
+            }
+            ("PUT", path) if path == format!("{RULES}/order") => {
+                *order = serde_json::from_value(req.json()["ruleIds"].clone()).unwrap();
+                StubResponse::json(200, "{}")
+            }
+            ("POST", RULES) => {
+                let id = format!("rule-{}", order.len() + 1);
+                order.push(id.clone());
+                StubResponse::json(200, &rule(&id, order.len()))
+            }
+            ("DELETE", path) if path.starts_with(RULES) => {
+                let id = path.rsplit('/').next().unwrap();
+                order.retain(|v| v != id);
+                StubResponse::json(200, "{}")
+            }
+            _ => StubResponse::json(404, r#"{"code": 5, "message": "not found"}"#),
+        };
+        after(&req.method, &mut order);
+        response
+    };
+    (order, handler)
+}
+
+fn ids<T>(items: &[T], id: impl Fn(&T) -> &str) -> Vec<&str> {
+    items.iter().map(id).collect()
+}
+
+fn move_rule(id: &str, position: Position) -> RuleMoveRequest {
+    RuleMoveRequest {
+        namespace_key: None,
+        flag_key: "flag-a".into(),
+        id: id.into(),
+        position,
+    }
+}
+
+#[tokio::test]
+async fn move_rules() {
+    let (_, handler) = rules(&["a", "b", "c", "d"], |_, _| {});
+    let server = StubServer::start(handler).await;
+    let rules = client(&server, AuthScheme::None).api().rules();
+
+    let moved = rules.move_to(&move_rule("c", Position::Top)).await.unwrap();
+    assert_eq!(ids(&moved, |r| &r.id), ["c", "a", "b", "d"]);
+    assert_eq!(moved[0].rank, 1);
+
+    let moved = rules
+        .move_to(&move_rule("c", Position::Bottom))
+        .await
+        .unwrap();
+    assert_eq!(ids(&moved, |r| &r.id), ["a", "b", "d", "c"]);
+
+    let moved = rules.move_to(&move_rule("d", Position::Up)).await.unwrap();
+    assert_eq!(ids(&moved, |r| &r.id), ["a", "d", "b", "c"]);
+
+    let moved = rules
+        .move_to(&move_rule("a", Position::Down))
+        .await
+        .unwrap();
+    assert_eq!(ids(&moved, |r| &r.id), ["d", "a", "b", "c"]);
+
+    let moved = rules
+        .move_to(&move_rule("d", Position::Rank(3)))
+        .await
+        .unwrap();
+    assert_eq!(ids(&moved, |r| &r.id), ["a", "b", "d", "c"]);
+
+    let moved = rules
+        .move_to(&move_rule("a", Position::Rank(10)))
+        .await
+        .unwrap();
+    assert_eq!(ids(&moved, |r| &r.id), ["b", "d", "c", "a"]);
+}
+
+#[tokio::test]
+async fn unchanged_order_is_not_submitted() {
+    let (_, handler) = rules(&["a", "b"], |_, _| {});
+    let server = StubServer::start(handler).await;
+
+    let moved = client(&server, AuthScheme::None)
+        .api()
+        .rules()
+        .move_to(&move_rule("a", Position::Up))
+        .await
+        .unwrap();
+
+    assert_eq!(ids(&moved, |r| &r.id), ["a", "b"]);
+    assert!(server.requests().iter().all(|r| r.method == "GET"));
+}
+
+#[tokio::test]
+async fn swap_rules() {
+    let (order, handler) = rules(&["a", "b", "c"], |_, _| {});
+    let server = StubServer::start(handler).await;
+
+    client(&server, AuthScheme::None)
+        .api()
+        .rules()
+        .swap(&RuleSwapRequest {
+            flag_key: "flag-a".into(),
+            first: "a".into(),
+            second: "c".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(*order.lock().unwrap(), ["c", "b", "a"]);
+    let put = server
+        .requests()
+        .into_iter()
+        .find(|r| r.method == "PUT")
+        .expect("order request");
+    assert_eq!(put.path, format!("{RULES}/order"));
+    assert_eq!(put.json()["ruleIds"], serde_json::json!(["c", "b", "a"]));
+}
+
+#[tokio::test]
+async fn create_rule_at_position() {
+    let (order, handler) = rules(&["rule-1", "rule-2"], |_, _| {});
+    let server = StubServer::start(handler).await;
+
+    let rule = client(&server, AuthScheme::None)
+        .api()
+        .rules()
+        .create_at(
+            &RuleCreateRequest {
+                flag_key: "flag-a".into(),
+                segment_key: Some("segment-a".into()),
+                ..Default::default()
+            },
+            Position::Top,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(rule.id, "rule-3");
+    assert_eq!(rule.rank, 1);
+    assert_eq!(*order.lock().unwrap(), ["rule-3", "rule-1", "rule-2"]);
+    let create = server
+        .requests()
+        .into_iter()
+        .find(|r| r.method == "POST")
+        .expect("create request");
+    assert_eq!(create.json()["rank"], 3);
+}
+
+#[tokio::test]
+async fn create_at_deletes_rule_it_cannot_move() {
+    // Another client adds a rule while ours is being moved into place.
+    let lists = AtomicUsize::new(0);
+    let (order, handler) = rules(&["rule-1", "rule-2"], move |method, order| {
+        if method == "GET" && lists.fetch_add(1, Ordering::SeqCst) == 1 {
+            order.push("other".into());
+        }
+    });
+    let server = StubServer::start(handler).await;
+
+    let err = client(&server, AuthScheme::None)
+        .api()
+        .rules()
+        .create_at(
+            &RuleCreateRequest {
+                flag_key: "flag-a".into(),
+                segment_key: Some("segment-a".into()),
+                ..Default::default()
+            },
+            Position::Top,
+        )
+        .await
+        .expect_err("conflict");
+
+    assert!(matches!(err, Error::Conflict(_)), "{err:?}");
+    assert_eq!(*order.lock().unwrap(), ["rule-1", "rule-2", "other"]);
+    let delete = server.requests().pop().expect("delete request");
+    assert_eq!(delete.method, "DELETE");
+    assert_eq!(delete.path, format!("{RULES}/rule-3"));
+}
+
+#[tokio::test]
+async fn concurrent_change_is_a_conflict() {
+    // Another client adds a rule right after ours is reordered.
+    let (_, handler) = rules(&["a", "b"], |method, order| {
+        if method == "PUT" {
+            order.push("c".into());
+        }
+    });
+    let server = StubServer::start(handler).await;
+
+    let err = client(&server, AuthScheme::None)
+        .api()
+        .rules()
+        .move_to(&move_rule("b", Position::Top))
+        .await
+        .expect_err("conflict");
+
+    assert!(matches!(err, Error::Conflict(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn change_before_submitting_is_a_conflict() {
+    // Another client adds a rule right after ours first reads the order.
+    let listed = AtomicBool::new(false);
+    let (order, handler) = rules(&["a", "b"], move |method, order| {
+        if method == "GET" && !listed.swap(true, Ordering::SeqCst) {
+            order.push("c".into());
+        }
+    });
+    let server = StubServer::start(handler).await;
+
+    let err = client(&server, AuthScheme::None)
+        .api()
+        .rules()
+        .move_to(&move_rule("b", Position::Top))
+        .await
+        .expect_err("conflict");
+
+    assert!(matches!(err, Error::Conflict(_)), "{err:?}");
+    assert!(server.requests().iter().all(|r| r.method == "GET"));
+    assert_eq!(*order.lock().unwrap(), ["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn missing_rule_is_not_found() {
+    let (_, handler) = rules(&["a", "b"], |_, _| {});
+    let server = StubServer::start(handler).await;
+
+    let err = client(&server, AuthScheme::None)
+        .api()
+        .rules()
+        .move_to(&move_rule("deleted", Position::Top))
+        .await
+        .expect_err("not found");
+
+    assert!(matches!(err, Error::NotFound(_)), "{err:?}");
+    assert_eq!(err.to_string(), "deleted is not in the current order");
+    assert!(server.requests().iter().all(|r| r.method == "GET"));
+}
+
+#[tokio::test]
+async fn move_rollouts() {
+    let order = Arc::new(Mutex::new(vec!["a".to_string(), "b".to_string()]));
+    let state = order.clone();
+    let server = StubServer::start(move |req| {
+        let mut order = state.lock().unwrap();
+        if req.method == "PUT" {
+            assert_eq!(req.path, format!("{ROLLOUTS}/order"));
+            *order = serde_json::from_value(req.json()["rolloutIds"].clone()).unwrap();
+            return StubResponse::json(200, "{}");
+        }
+        let rollouts: Vec<_> = order
+            .iter()
+            .enumerate()
+            .map(|(i, id)| rollout(id, i + 1))
+            .collect();
+        StubResponse::json(
+            200,
+            &format!(
+                r#"{{"rules": [{}], "nextPageToken": "", "totalCount": {}}}"#,
+                rollouts.join(","),
+                rollouts.len()
+            ),
+        )
+    })
+    .await;
+
+    let moved = client(&server, AuthScheme::None)
+        .api()
+        .rollouts()
+        .move_to(&RolloutMoveRequest {
+            namespace_key: None,
+            flag_key: "flag-a".into(),
+            id: "b".into(),
+            position: Position::Top,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(ids(&moved, |r| &r.id), ["b", "a"]);
+    assert_eq!(*order.lock().unwrap(), ["b", "a"]);
+}
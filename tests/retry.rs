@@ -0,0 +1,190 @@
+mod common;
+
+use common::{config, StubResponse, StubServer, FLAG};
+use flipt::api::flag::{FlagCreateRequest, FlagGetRequest};
+use flipt::evaluation::EvaluateRequest;
+use flipt::retry::RetryPolicy;
+use flipt::{AuthScheme, FliptClient};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+const BOOLEAN_EVALUATION: &str = r#"{
+    "enabled": true,
+    "reason": "DEFAULT_EVALUATION_REASON",
+    "requestId": "request-a",
+    "requestDurationMillis": 1.0,
+    "timestamp": "2023-01-01T00:00:00Z",
+    "flagKey": "flag-a"
+}"#;
+
+const UNAVAILABLE: &str = r#"{"code": 14, "message": "unavailable", "details": []}"#;
+
+/// Responds with `status` for the first `failures` requests, then `body`.
+async fn flaky_server(failures: usize, status: u16, body: &'static str) -> StubServer {
+    let count = AtomicUsize::new(0);
+    StubServer::start(move |_| {
+        if count.fetch_add(1, Ordering::SeqCst) < failures {
+            StubResponse::json(status, UNAVAILABLE)
+        } else {
+            StubResponse::json(200, body)
+        }
+    })
+    .await
+}
+
+fn client(server: &StubServer, policy: RetryPolicy) -> FliptClient {
+    FliptClient::new(config(server, AuthScheme::None).set_retry_policy(policy))
+        .expect("build client")
+}
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy::new()
+        .set_base_backoff(Duration::from_millis(1))
+        .set_max_backoff(Duration::from_millis(5))
+}
+
+#[tokio::test]
+async fn retries_idempotent_request_until_success() {
+    let server = flaky_server(2, 503, FLAG).await;
+    let client = client(&server, fast_policy().set_max_attempts(3));
+
+    let flag = client
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            key: "flag-a".into(),
+            ..Default::default()
+        })
+        .await
+        .expect("get flag");
+
+    assert_eq!(flag.key, "flag-a");
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let server = flaky_server(5, 502, FLAG).await;
+    let client = client(&server, fast_policy().set_max_attempts(2));
+
+    let result = client
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            key: "flag-a".into(),
+            ..Default::default()
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn does_not_retry_non_idempotent_post() {
+    let server = flaky_server(1, 503, FLAG).await;
+    let client = client(&server, fast_policy());
+
+    let result = client
+        .api()
+        .flags()
+        .create(&FlagCreateRequest {
+            key: "flag-a".into(),
+            ..Default::default()
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn retries_evaluation_post() {
+    let server = flaky_server(1, 503, BOOLEAN_EVALUATION).await;
+    let client = client(&server, fast_policy());
+
+    let evaluation = client
+        .evaluation()
+        .boolean(&EvaluateRequest {
+            namespace_key: "default".into(),
+            flag_key: "flag-a".into(),
+            entity_id: "entity".into(),
+            ..Default::default()
+        })
+        .await
+        .expect("boolean evaluation");
+
+    assert!(evaluation.enabled);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn does_not_retry_unlisted_status() {
+    let server = flaky_server(1, 500, FLAG).await;
+    let client = client(&server, fast_policy());
+
+    let result = client
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            key: "flag-a".into(),
+            ..Default::default()
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn honors_retry_after() {
+    let count = AtomicUsize::new(0);
+    let server = StubServer::start(move |_| {
+        if count.fetch_add(1, Ordering::SeqCst) == 0 {
+            StubResponse::json(429, UNAVAILABLE).header("Retry-After", "1")
+        } else {
+            StubResponse::json(200, FLAG)
+        }
+    })
+    .await;
+    let client = client(&server, fast_policy());
+
+    let start = Instant::now();
+    client
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            key: "flag-a".into(),
+            ..Default::default()
+        })
+        .await
+        .expect("get flag");
+
+    assert!(start.elapsed() >= Duration::from_secs(1));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn gives_up_when_retry_after_exceeds_limit() {
+    let server =
+        StubServer::start(|_| StubResponse::json(503, UNAVAILABLE).header("Retry-After", "3600"))
+            .await;
+    let client = client(
+        &server,
+        fast_policy().set_max_retry_after(Duration::from_secs(1)),
+    );
+
+    let start = Instant::now();
+    let result = client
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            key: "flag-a".into(),
+            ..Default::default()
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(server.requests().len(), 1);
+}
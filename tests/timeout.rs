@@ -0,0 +1,120 @@
+mod common;
+
+use common::{config, StubResponse, StubServer, FLAG};
+use flipt::api::flag::FlagGetRequest;
+use flipt::error::Error;
+use flipt::evaluation::EvaluateRequest;
+use flipt::{AuthScheme, Config, FliptClient};
+use std::time::{Duration, Instant};
+
+const BOOLEAN_EVALUATION: &str = r#"{
+    "enabled": true,
+    "reason": "DEFAULT_EVALUATION_REASON",
+    "requestId": "request-a",
+    "requestDurationMillis": 1.0,
+    "timestamp": "2023-01-01T00:00:00Z",
+    "flagKey": "flag-a"
+}"#;
+
+async fn slow_server(body: &'static str, delay: Duration) -> StubServer {
+    StubServer::start(move |_| StubResponse::json(200, body).delay(delay)).await
+}
+
+fn flag_request() -> FlagGetRequest {
+    FlagGetRequest {
+        key: "flag-a".into(),
+        ..Default::default()
+    }
+}
+
+fn evaluate_request() -> EvaluateRequest {
+    EvaluateRequest {
+        namespace_key: "default".into(),
+        flag_key: "flag-a".into(),
+        entity_id: "entity".into(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn request_timeout_from_config() {
+    let server = slow_server(FLAG, Duration::from_millis(500)).await;
+    let config = config(&server, AuthScheme::None).set_request_timeout(Duration::from_millis(50));
+    let client = FliptClient::new(config).expect("build client");
+
+    let err = client
+        .api()
+        .flags()
+        .get(&flag_request())
+        .await
+        .expect_err("timed out");
+
+    assert!(matches!(err, Error::Timeout(None)), "{err:?}");
+}
+
+#[tokio::test]
+async fn per_call_timeout_is_tighter_than_config() {
+    let server = slow_server(BOOLEAN_EVALUATION, Duration::from_millis(500)).await;
+    let config = config(&server, AuthScheme::None).set_request_timeout(Duration::from_secs(5));
+    let client = FliptClient::new(config).expect("build client");
+
+    let err = client
+        .evaluation()
+        .with_timeout(Duration::from_millis(50))
+        .boolean(&evaluate_request())
+        .await
+        .expect_err("timed out");
+
+    assert!(matches!(err, Error::Timeout(None)), "{err:?}");
+}
+
+#[tokio::test]
+async fn timeout_covers_retries() {
+    let server = slow_server(BOOLEAN_EVALUATION, Duration::from_millis(500)).await;
+    // The default retry policy retries timed out attempts.
+    let config = Config::new(server.endpoint(), AuthScheme::None);
+    let client = FliptClient::new(config).expect("build client");
+
+    let start = Instant::now();
+    let err = client
+        .evaluation()
+        .with_timeout(Duration::from_millis(100))
+        .boolean(&evaluate_request())
+        .await
+        .expect_err("timed out");
+
+    assert!(matches!(err, Error::Timeout(None)), "{err:?}");
+    assert!(
+        start.elapsed() < Duration::from_millis(200),
+        "{:?}",
+        start.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn per_call_timeout_is_looser_than_config() {
+    let server = slow_server(FLAG, Duration::from_millis(200)).await;
+    let config = config(&server, AuthScheme::None).set_request_timeout(Duration::from_millis(50));
+    let client = FliptClient::new(config).expect("build client");
+
+    let flag = client
+        .api()
+        .flags()
+        .with_timeout(Duration::from_secs(5))
+        .get(&flag_request())
+        .await
+        .expect("get flag");
+
+    assert_eq!(flag.key, "flag-a");
+}
+
+#[test]
+fn invalid_timeout_from_env() {
+    std::env::set_var("FLIPT_ENDPOINT", "http://localhost:8080");
+    std::env::set_var("FLIPT_REQUEST_TIMEOUT", "soon");
+
+    let result = Config::new_from_env();
+
+    std::env::remove_var("FLIPT_REQUEST_TIMEOUT");
+    assert!(result.is_err());
+}
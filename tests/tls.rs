@@ -0,0 +1,172 @@
+mod common;
+
+use common::{config, StubResponse, StubServer};
+use flipt::error::Error;
+use flipt::retry::RetryPolicy;
+use flipt::tls::{Pem, TlsConfig};
+use flipt::{AuthScheme, Config, FliptClient};
+use rcgen::{
+    BasicConstraints, CertificateParams, CertifiedKey, DnType, IsCa, KeyPair, KeyUsagePurpose,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+const INFO: &str = r#"{"build": {"version": "v1.0.0"}}"#;
+
+/// A certificate authority issuing server and client certificates.
+struct Ca {
+    cert: rcgen::Certificate,
+    key: KeyPair,
+}
+
+impl Ca {
+    fn new() -> Self {
+        let mut params = CertificateParams::new(Vec::new()).unwrap();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "flipt test ca");
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign];
+        let key = KeyPair::generate().unwrap();
+        let cert = params.self_signed(&key).unwrap();
+        Self { cert, key }
+    }
+
+    fn issue(&self, name: &str) -> CertifiedKey {
+        let params = CertificateParams::new(vec![name.to_string()]).unwrap();
+        let key_pair = KeyPair::generate().unwrap();
+        let cert = params.signed_by(&key_pair, &self.cert, &self.key).unwrap();
+        CertifiedKey { cert, key_pair }
+    }
+
+    fn pem(&self) -> Vec<u8> {
+        self.cert.pem().into_bytes()
+    }
+}
+
+fn acceptor(ca: &Ca, require_client_cert: bool) -> TlsAcceptor {
+    let server = ca.issue("localhost");
+    let provider = Arc::new(ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .unwrap();
+    let builder = if require_client_cert {
+        let mut roots = RootCertStore::empty();
+        roots.add(ca.cert.der().clone()).unwrap();
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+            .build()
+            .unwrap();
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+    let key = PrivateKeyDer::from_pem_slice(server.key_pair.serialize_pem().as_bytes()).unwrap();
+    let config = builder
+        .with_single_cert(vec![CertificateDer::from(server.cert.der().to_vec())], key)
+        .unwrap();
+    TlsAcceptor::from(Arc::new(config))
+}
+
+fn write_temp(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("flipt-tls-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+async fn get_info(config: Config) -> flipt::error::Result<()> {
+    let client = FliptClient::new(config)?;
+    client.meta().info().get().await.map(|_| ())
+}
+
+#[tokio::test]
+async fn trusts_custom_ca() {
+    let ca = Ca::new();
+    let server =
+        StubServer::start_tls(acceptor(&ca, false), |_| StubResponse::json(200, INFO)).await;
+
+    let config = config(&server, AuthScheme::None)
+        .set_tls(TlsConfig::new().add_root_certificate(Pem::Bytes(ca.pem())));
+
+    get_info(config).await.expect("get info");
+}
+
+#[tokio::test]
+async fn rejects_unknown_ca() {
+    let ca = Ca::new();
+    let server =
+        StubServer::start_tls(acceptor(&ca, false), |_| StubResponse::json(200, INFO)).await;
+
+    let err = get_info(config(&server, AuthScheme::None))
+        .await
+        .expect_err("get info");
+
+    assert!(matches!(err, Error::Request(_)), "{err:?}");
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn insecure_skip_verify() {
+    let ca = Ca::new();
+    let server =
+        StubServer::start_tls(acceptor(&ca, false), |_| StubResponse::json(200, INFO)).await;
+
+    let config =
+        config(&server, AuthScheme::None).set_tls(TlsConfig::new().set_insecure_skip_verify(true));
+
+    get_info(config).await.expect("get info");
+}
+
+#[tokio::test]
+async fn mutual_tls() {
+    let ca = Ca::new();
+    let server =
+        StubServer::start_tls(acceptor(&ca, true), |_| StubResponse::json(200, INFO)).await;
+    let tls = TlsConfig::new().add_root_certificate(Pem::Bytes(ca.pem()));
+
+    let err = get_info(config(&server, AuthScheme::None).set_tls(tls.clone()))
+        .await
+        .expect_err("without client certificate");
+    assert!(matches!(err, Error::Request(_)), "{err:?}");
+
+    let client = ca.issue("client");
+    let tls = tls.set_client_identity(
+        Pem::Bytes(client.cert.pem().into_bytes()),
+        Pem::Bytes(client.key_pair.serialize_pem().into_bytes()),
+    );
+    get_info(config(&server, AuthScheme::None).set_tls(tls))
+        .await
+        .expect("with client certificate");
+}
+
+#[tokio::test]
+async fn from_env() {
+    let ca = Ca::new();
+    let server =
+        StubServer::start_tls(acceptor(&ca, true), |_| StubResponse::json(200, INFO)).await;
+    let client = ca.issue("client");
+
+    std::env::set_var("FLIPT_ENDPOINT", server.endpoint().as_str());
+    std::env::set_var("FLIPT_CA_CERT_FILE", write_temp("ca.pem", &ca.cert.pem()));
+    std::env::set_var(
+        "FLIPT_CLIENT_CERT_FILE",
+        write_temp("client.pem", &client.cert.pem()),
+    );
+    std::env::set_var(
+        "FLIPT_CLIENT_KEY_FILE",
+        write_temp("client-key.pem", &client.key_pair.serialize_pem()),
+    );
+
+    let config = Config::new_from_env()
+        .expect("config")
+        .set_retry_policy(RetryPolicy::disabled());
+    get_info(config).await.expect("get info");
+
+    std::env::remove_var("FLIPT_CLIENT_CERT_FILE");
+    assert!(matches!(Config::new_from_env(), Err(Error::Config(_))));
+}
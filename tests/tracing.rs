@@ -0,0 +1,169 @@
+#![cfg(feature = "tracing")]
+
+mod common;
+
+use common::{client, StubResponse, StubServer};
+use flipt::api::flag::FlagGetRequest;
+use flipt::evaluation::EvaluateRequest;
+use flipt::AuthScheme;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+const VARIANT_EVALUATION: &str = r#"{
+    "match": true,
+    "segmentKeys": ["segment-a"],
+    "reason": "MATCH_EVALUATION_REASON",
+    "variantKey": "blue",
+    "variantAttachment": "",
+    "requestId": "request-a",
+    "requestDurationMillis": 1.0,
+    "timestamp": "2023-01-01T00:00:00Z",
+    "flagKey": "flag-a"
+}"#;
+
+type Fields = HashMap<String, String>;
+
+/// Collects the fields of closed spans and of events.
+#[derive(Clone, Default)]
+struct Capture {
+    spans: Arc<Mutex<Vec<Fields>>>,
+    events: Arc<Mutex<Vec<Fields>>>,
+}
+
+struct Visitor<'a>(&'a mut Fields);
+
+impl Visit for Visitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().into(), format!("{value:?}"));
+    }
+}
+
+impl<S> Layer<S> for Capture
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Fields::new();
+        attrs.record(&mut Visitor(&mut fields));
+        ctx.span(id).unwrap().extensions_mut().insert(fields);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let span = ctx.span(id).unwrap();
+        let mut extensions = span.extensions_mut();
+        values.record(&mut Visitor(extensions.get_mut::<Fields>().unwrap()));
+    }
+
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let mut fields = Fields::new();
+        event.record(&mut Visitor(&mut fields));
+        self.events.lock().unwrap().push(fields);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = ctx.span(&id).unwrap();
+        let fields = span.extensions_mut().remove::<Fields>();
+        self.spans.lock().unwrap().extend(fields);
+    }
+}
+
+#[tokio::test]
+async fn request_span() {
+    let server =
+        StubServer::start(|_| StubResponse::json(404, r#"{"code": 5, "message": "not found"}"#))
+            .await;
+    let capture = Capture::default();
+    let _guard = tracing::subscriber::set_default(Registry::default().with(capture.clone()));
+
+    let result = client(&server, AuthScheme::None)
+        .api()
+        .flags()
+        .get(&FlagGetRequest {
+            namespace_key: Some("production".into()),
+            key: "flag-a".into(),
+        })
+        .await;
+    assert!(result.is_err());
+
+    let spans = capture.spans.lock().unwrap();
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+    assert_eq!(span["http.request.method"], "GET");
+    assert_eq!(
+        span["url.template"],
+        "/api/v1/namespaces/{namespace_key}/flags/{flag_key}"
+    );
+    assert_eq!(span["flipt.namespace_key"], "production");
+    assert_eq!(span["feature_flag.key"], "flag-a");
+    assert_eq!(span["http.response.status_code"], "404");
+    assert_eq!(span["error.type"], "not_found");
+    assert!(span.contains_key("duration_ms"));
+}
+
+#[tokio::test]
+async fn feature_flag_event() {
+    let server = StubServer::start(|_| StubResponse::json(200, VARIANT_EVALUATION)).await;
+    let capture = Capture::default();
+    let _guard = tracing::subscriber::set_default(Registry::default().with(capture.clone()));
+
+    client(&server, AuthScheme::None)
+        .evaluation()
+        .variant(&EvaluateRequest {
+            namespace_key: "default".into(),
+            flag_key: "flag-a".into(),
+            entity_id: "entity".into(),
+            ..Default::default()
+        })
+        .await
+        .expect("evaluate");
+
+    let events = capture.events.lock().unwrap();
+    let event = events
+        .iter()
+        .find(|e| e.contains_key("feature_flag.key"))
+        .expect("feature_flag event");
+    assert_eq!(event["feature_flag.key"], "flag-a");
+    assert_eq!(event["feature_flag.provider_name"], "flipt");
+    assert_eq!(event["feature_flag.variant"], "blue");
+    assert_eq!(event["feature_flag.evaluation.reason"], "targeting_match");
+}
+
+#[cfg(feature = "opentelemetry")]
+#[tokio::test]
+async fn injects_traceparent() {
+    use opentelemetry::trace::{TraceContextExt, TracerProvider};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let server = StubServer::start(|_| StubResponse::json(200, "{}")).await;
+    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder().build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("test"));
+    let _guard = tracing::subscriber::set_default(Registry::default().with(layer));
+
+    let parent = tracing::info_span!("parent");
+    let trace_id = parent.context().span().span_context().trace_id();
+    let _ = tracing::Instrument::instrument(
+        client(&server, AuthScheme::None).meta().info().get(),
+        parent,
+    )
+    .await;
+
+    let requests = server.requests();
+    let traceparent = requests[0].header("traceparent").expect("traceparent");
+    let parts: Vec<&str> = traceparent.split('-').collect();
+    assert_eq!(parts.len(), 4);
+    assert_eq!(parts[0], "00");
+    assert_eq!(parts[1], trace_id.to_string());
+    assert_eq!(parts[2].len(), 16);
+    assert_eq!(parts[3], "01");
+}